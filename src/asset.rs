@@ -45,7 +45,6 @@ impl_plugin_with_assets!(
     }
     dynamic: {}
     init: {
-        crate::terminal::TerminalScreenTarget,
         crate::office::OfficeAssets,
         // crate::ui::UiAssets,
         // crate::code::LoCMesh,
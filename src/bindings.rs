@@ -0,0 +1,497 @@
+use crate::{prelude::*, unwrap_or_continue};
+use bevy::{
+    input::InputSystem,
+    utils::{HashMap, HashSet},
+};
+use std::fmt;
+
+/// where the controls can be remapped, every line is `<Action> = <binding>, <binding>...`
+/// actions left out of the file keep their default bindings
+const BINDINGS_PATH: &str = "assets/settings/bindings.txt";
+/// stick movement smaller than this is ignored
+const STICK_DEADZONE: f32 = 0.15;
+
+pub struct BindingsPlugin;
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load())
+            .init_resource::<Actions>()
+            .add_system_to_stage(CoreStage::PreUpdate, Actions::update.after(InputSystem));
+    }
+}
+
+/// `fn $fn(&str) -> Option<$ty>` for the variants listed, spelled the same as in the code
+macro_rules! from_name {
+    ($fn:ident -> $ty:ident { $($variant:ident),* $(,)? }) => {
+        fn $fn(s: &str) -> Option<$ty> {
+            match s {
+                $(stringify!($variant) => Some($ty::$variant),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    /// pick up, attach, put into a bundle
+    Interact,
+    Swing,
+    /// held to charge
+    Throw,
+    /// swap with what's looked at, or pull a plank out of a bundle
+    Swap,
+    OpenTerminal,
+    /// turns what's held with the physics grab instead of looking around
+    RotateHeld,
+    Slot1,
+    Slot2,
+    Slot3,
+    NextSlot,
+    PrevSlot,
+}
+
+from_name!(action_from_name -> Action {
+    MoveForward, MoveBack, MoveLeft, MoveRight, Interact, Swing, Throw, Swap,
+    OpenTerminal, RotateHeld, Slot1, Slot2, Slot3, NextSlot, PrevSlot,
+});
+
+from_name!(key_from_name -> KeyCode {
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    Up, Down, Left, Right, Space, Tab, Return, Back, Escape,
+    LShift, RShift, LControl, RControl, LAlt, RAlt,
+});
+
+from_name!(mouse_from_name -> MouseButton { Left, Right, Middle });
+
+from_name!(pad_from_name -> GamepadButtonType {
+    South, East, North, West, LeftTrigger, LeftTrigger2, RightTrigger, RightTrigger2,
+    Select, Start, LeftThumb, RightThumb, DPadUp, DPadDown, DPadLeft, DPadRight,
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Pad(GamepadButtonType),
+}
+
+impl Binding {
+    /// `key:W`, `mouse:Left` or `pad:South`
+    fn parse(s: &str) -> Option<Self> {
+        let (kind, name) = s.split_once(':')?;
+        match kind {
+            "key" => key_from_name(name).map(Binding::Key),
+            "mouse" => mouse_from_name(name).map(Binding::Mouse),
+            "pad" => pad_from_name(name).map(Binding::Pad),
+            _ => None,
+        }
+    }
+}
+
+/// the name shown in control prompts, pad buttons by their usual face labels
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use GamepadButtonType::*;
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name.strip_prefix("Key").unwrap_or(&name);
+                write!(f, "{}", name.to_uppercase())
+            }
+            Binding::Mouse(MouseButton::Left) => write!(f, "MOUSE1"),
+            Binding::Mouse(MouseButton::Right) => write!(f, "MOUSE2"),
+            Binding::Mouse(MouseButton::Middle) => write!(f, "MOUSE3"),
+            Binding::Mouse(MouseButton::Other(n)) => write!(f, "MOUSE{n}"),
+            Binding::Pad(button) => match button {
+                South => write!(f, "A"),
+                East => write!(f, "B"),
+                West => write!(f, "X"),
+                North => write!(f, "Y"),
+                LeftTrigger => write!(f, "LB"),
+                RightTrigger => write!(f, "RB"),
+                LeftTrigger2 => write!(f, "LT"),
+                RightTrigger2 => write!(f, "RT"),
+                LeftThumb => write!(f, "LS"),
+                RightThumb => write!(f, "RS"),
+                DPadUp => write!(f, "UP"),
+                DPadDown => write!(f, "DOWN"),
+                DPadLeft => write!(f, "LEFT"),
+                DPadRight => write!(f, "RIGHT"),
+                other => write!(f, "{}", format!("{other:?}").to_uppercase()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+impl Stick {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Left" => Some(Stick::Left),
+            "Right" => Some(Stick::Right),
+            _ => None,
+        }
+    }
+
+    fn axes(&self) -> (GamepadAxisType, GamepadAxisType) {
+        match self {
+            Stick::Left => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+            Stick::Right => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+        }
+    }
+}
+
+/// What sets off each action, plus which sticks walk and look
+#[derive(Debug, Clone)]
+pub struct InputBindings {
+    pub actions: HashMap<Action, Vec<Binding>>,
+    pub move_stick: Stick,
+    pub look_stick: Stick,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use Action::*;
+        use Binding::*;
+        use GamepadButtonType::*;
+        let actions = [
+            (MoveForward, vec![Key(KeyCode::W), Pad(DPadUp)]),
+            (MoveBack, vec![Key(KeyCode::S), Pad(DPadDown)]),
+            (MoveLeft, vec![Key(KeyCode::A), Pad(DPadLeft)]),
+            (MoveRight, vec![Key(KeyCode::D), Pad(DPadRight)]),
+            (Interact, vec![Mouse(MouseButton::Left), Pad(South)]),
+            (Swing, vec![Mouse(MouseButton::Left), Pad(RightTrigger2)]),
+            (Throw, vec![Mouse(MouseButton::Right), Pad(LeftTrigger2)]),
+            (Swap, vec![Mouse(MouseButton::Right), Pad(West)]),
+            (OpenTerminal, vec![Mouse(MouseButton::Left), Pad(South)]),
+            (RotateHeld, vec![Key(KeyCode::LShift), Pad(North)]),
+            (Slot1, vec![Key(KeyCode::Key1)]),
+            (Slot2, vec![Key(KeyCode::Key2)]),
+            (Slot3, vec![Key(KeyCode::Key3)]),
+            (NextSlot, vec![Pad(RightTrigger)]),
+            (PrevSlot, vec![Pad(LeftTrigger)]),
+        ];
+        Self {
+            actions: actions.into_iter().collect(),
+            move_stick: Stick::Left,
+            look_stick: Stick::Right,
+        }
+    }
+}
+
+impl InputBindings {
+    /// `[MOUSE1/A]`, every binding for `action` the way a prompt shows it
+    pub fn prompt(&self, action: Action) -> String {
+        let binds = self.actions.get(&action).map(Vec::as_slice).unwrap_or(&[]);
+        match binds.is_empty() {
+            true => "[unbound]".to_owned(),
+            false => {
+                let names = binds.iter().map(|b| b.to_string()).collect::<Vec<_>>();
+                format!("[{}]", names.join("/"))
+            }
+        }
+    }
+
+    /// swaps every `{Action}` in `text` for its prompt, anything else in braces is left alone
+    pub fn fill(&self, text: &str) -> String {
+        let mut filled = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find('{') {
+            filled.push_str(&rest[..open]);
+            rest = &rest[open..];
+            let action = rest
+                .find('}')
+                .and_then(|close| Some((action_from_name(&rest[1..close])?, close)));
+            match action {
+                Some((action, close)) => {
+                    filled.push_str(&self.prompt(action));
+                    rest = &rest[close + 1..];
+                }
+                None => {
+                    filled.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        filled.push_str(rest);
+        filled
+    }
+
+    fn load() -> Self {
+        let mut bindings = Self::default();
+        // no file, or no filesystem on the web, just means the defaults
+        if let Ok(file) = std::fs::read_to_string(BINDINGS_PATH) {
+            bindings.apply(&file);
+        }
+        bindings
+    }
+
+    fn apply(&mut self, file: &str) {
+        let lines = file
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+        for line in lines {
+            let (name, value) = unwrap_or_continue!(line.split_once('=');
+                else warn!("{BINDINGS_PATH}: can't read `{line}`"));
+            let (name, value) = (name.trim(), value.trim());
+            match name {
+                "MoveStick" | "LookStick" => {
+                    let stick = unwrap_or_continue!(Stick::parse(value);
+                        else warn!("{BINDINGS_PATH}: {name} has to be Left or Right"));
+                    match name {
+                        "MoveStick" => self.move_stick = stick,
+                        _ => self.look_stick = stick,
+                    }
+                }
+                _ => {
+                    let action = unwrap_or_continue!(action_from_name(name);
+                        else warn!("{BINDINGS_PATH}: there's no action called {name}"));
+                    let binds = value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|b| !b.is_empty())
+                        .filter_map(|b| {
+                            let bind = Binding::parse(b);
+                            if bind.is_none() {
+                                warn!("{BINDINGS_PATH}: can't bind {name} to {b}");
+                            }
+                            bind
+                        })
+                        .collect();
+                    self.actions.insert(action, binds);
+                }
+            }
+        }
+    }
+}
+
+/// This frame's actions, worked out from every keyboard, mouse and gamepad binding
+#[derive(Debug, Clone, Default)]
+pub struct Actions {
+    pressed: HashSet<Action>,
+    /// in the order they're declared, so the same press always resolves the same way
+    just_pressed: Vec<Action>,
+    /// from the move stick, x to the right and y forwards
+    pub movement: Vec2,
+    /// from the look stick, x to the right and y up
+    pub look: Vec2,
+}
+
+impl Actions {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = &Action> {
+        self.just_pressed.iter()
+    }
+
+    /// nothing later in the frame sees what was just pressed
+    pub fn clear_just_pressed(&mut self) {
+        self.just_pressed.clear();
+    }
+
+    /// nothing later in the frame sees `action` was just pressed
+    pub fn consume(&mut self, action: Action) {
+        self.just_pressed.retain(|a| *a != action);
+    }
+
+    fn update(
+        mut actions: ResMut<Actions>,
+        bindings: Res<InputBindings>,
+        keys: Res<Input<KeyCode>>,
+        mouse: Res<Input<MouseButton>>,
+        gamepads: Res<Gamepads>,
+        pad_buttons: Res<Input<GamepadButton>>,
+        pad_axes: Res<Axis<GamepadAxis>>,
+    ) {
+        let pad_pressed = |button: GamepadButtonType, just: bool| {
+            gamepads.iter().any(|pad| match just {
+                true => pad_buttons.just_pressed(GamepadButton(*pad, button)),
+                false => pad_buttons.pressed(GamepadButton(*pad, button)),
+            })
+        };
+        let is_down = |bind: &Binding, just: bool| match (bind, just) {
+            (Binding::Key(k), false) => keys.pressed(*k),
+            (Binding::Key(k), true) => keys.just_pressed(*k),
+            (Binding::Mouse(m), false) => mouse.pressed(*m),
+            (Binding::Mouse(m), true) => mouse.just_pressed(*m),
+            (Binding::Pad(p), just) => pad_pressed(*p, just),
+        };
+
+        let mut pressed = HashSet::default();
+        let mut just_pressed = Vec::new();
+        for (action, binds) in bindings.actions.iter() {
+            if binds.iter().any(|b| is_down(b, false)) {
+                pressed.insert(*action);
+            }
+            if binds.iter().any(|b| is_down(b, true)) {
+                just_pressed.push(*action);
+            }
+        }
+        just_pressed.sort();
+        actions.pressed = pressed;
+        actions.just_pressed = just_pressed;
+
+        let stick = |stick: Stick| {
+            let (x, y) = stick.axes();
+            let tilt = gamepads
+                .iter()
+                .map(|pad| {
+                    Vec2::new(
+                        pad_axes.get(GamepadAxis(*pad, x)).unwrap_or(0.0),
+                        pad_axes.get(GamepadAxis(*pad, y)).unwrap_or(0.0),
+                    )
+                })
+                .sum::<Vec2>();
+            match tilt.length() < STICK_DEADZONE {
+                true => Vec2::ZERO,
+                false => tilt.clamp_length_max(1.0),
+            }
+        };
+        actions.movement = stick(bindings.move_stick);
+        actions.look = stick(bindings.look_stick);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings() {
+        assert_eq!(Binding::parse("key:W"), Some(Binding::Key(KeyCode::W)));
+        assert_eq!(
+            Binding::parse("key:Key1"),
+            Some(Binding::Key(KeyCode::Key1))
+        );
+        assert_eq!(
+            Binding::parse("mouse:Right"),
+            Some(Binding::Mouse(MouseButton::Right))
+        );
+        assert_eq!(
+            Binding::parse("pad:RightTrigger2"),
+            Some(Binding::Pad(GamepadButtonType::RightTrigger2))
+        );
+    }
+
+    #[test]
+    fn malformed_bindings() {
+        for s in ["", "W", "key:", ":W", "key W", "key:w", "Key:W", "key:W "] {
+            assert_eq!(Binding::parse(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn unknown_bindings() {
+        for s in ["key:F13", "mouse:Back", "pad:Guide", "wheel:Up"] {
+            assert_eq!(Binding::parse(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn apply_replaces_only_what_is_listed() {
+        let mut bindings = InputBindings::default();
+        bindings.apply(
+            "# comment\n\
+             \n\
+             Throw = key:Q, pad:East\n\
+             \x20 Swap=mouse:Middle \n\
+             MoveStick = Right\n\
+             LookStick = Left\n",
+        );
+        let defaults = InputBindings::default();
+        assert_eq!(
+            bindings.actions[&Action::Throw],
+            [
+                Binding::Key(KeyCode::Q),
+                Binding::Pad(GamepadButtonType::East)
+            ]
+        );
+        assert_eq!(
+            bindings.actions[&Action::Swap],
+            [Binding::Mouse(MouseButton::Middle)]
+        );
+        assert_eq!(
+            bindings.actions[&Action::Interact],
+            defaults.actions[&Action::Interact]
+        );
+        assert_eq!(bindings.move_stick, Stick::Right);
+        assert_eq!(bindings.look_stick, Stick::Left);
+    }
+
+    #[test]
+    fn apply_skips_bad_bindings_on_a_line() {
+        let mut bindings = InputBindings::default();
+        bindings.apply("Swing = key:Nope, mouse:Left, , pad:Nope");
+        assert_eq!(
+            bindings.actions[&Action::Swing],
+            [Binding::Mouse(MouseButton::Left)]
+        );
+        // nothing valid left unbinds it
+        bindings.apply("Swing = key:Nope");
+        assert!(bindings.actions[&Action::Swing].is_empty());
+    }
+
+    #[test]
+    fn apply_ignores_malformed_and_unknown_lines() {
+        let mut bindings = InputBindings::default();
+        bindings.apply(
+            "Throw key:Q\n\
+             Jump = key:Space\n\
+             throw = key:Q\n\
+             MoveStick = Middle\n",
+        );
+        let defaults = InputBindings::default();
+        assert_eq!(bindings.actions, defaults.actions);
+        assert_eq!(bindings.move_stick, defaults.move_stick);
+    }
+
+    #[test]
+    fn prompts() {
+        let mut bindings = InputBindings::default();
+        assert_eq!(bindings.prompt(Action::Interact), "[MOUSE1/A]");
+        assert_eq!(bindings.prompt(Action::RotateHeld), "[LSHIFT/Y]");
+        assert_eq!(bindings.prompt(Action::Slot1), "[1]");
+        bindings.apply("Throw = key:Q, pad:LeftTrigger2\nSwap = key:Nope");
+        assert_eq!(bindings.prompt(Action::Throw), "[Q/LT]");
+        assert_eq!(bindings.prompt(Action::Swap), "[unbound]");
+    }
+
+    #[test]
+    fn fill_only_replaces_actions() {
+        let bindings = InputBindings::default();
+        assert_eq!(
+            bindings.fill("{Swing} a {bundle} with {Slot3}{Interact}"),
+            "[MOUSE1/RT] a {bundle} with [3][MOUSE1/A]"
+        );
+        assert_eq!(bindings.fill("{Swing"), "{Swing");
+        assert_eq!(bindings.fill("}{{Slot2}"), "}{[2]");
+    }
+
+    #[test]
+    fn shipped_bindings_parse() {
+        let mut bindings = InputBindings::default();
+        bindings.apply(include_str!("../assets/settings/bindings.txt"));
+        for action in bindings.actions.keys() {
+            assert!(!bindings.actions[action].is_empty(), "{action:?}");
+        }
+    }
+}
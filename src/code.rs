@@ -4,9 +4,15 @@ use crate::{
     level::{Levels, NewLevel},
     office::SceneLocations,
     phys::group::collide::interactable_dynamic_body,
+    player::interactions::OrderedChildren,
     prelude::{phys::*, *},
-    terminal::{FontAtlas, TextSprite, TextSpriteBundle, ATLAS_CHAR_H, ATLAS_CHAR_W},
+    terminal::{
+        ansi, Align, FontAtlas, TextLayout, TextSprite, TextSpriteBundle, Wrap, ATLAS_CHAR_H,
+        ATLAS_CHAR_W,
+    },
+    unresult_or_continue,
 };
+use bevy::ecs::system::SystemParam;
 use bevy::render::{
     camera::RenderTarget,
     render_resource::{
@@ -16,6 +22,7 @@ use bevy::render::{
 };
 use bevy_asset_loader::asset_collection::AssetCollection;
 use rand::{rngs::ThreadRng, seq::SliceRandom};
+use std::collections::{HashMap, HashSet};
 
 pub struct CodePlugin;
 
@@ -26,6 +33,11 @@ impl Plugin for CodePlugin {
                 .run_in_state(GameState::InOffice)
                 .run_if(NewLevel::has_triggered),
         );
+        // after the command buffers of `Update` are applied, or planks leaving bundles go unseen
+        app.add_system_to_stage(
+            CoreStage::PostUpdate,
+            PlankLabel::number_planks.run_in_state(GameState::InOffice),
+        );
     }
 }
 
@@ -37,6 +49,8 @@ pub struct LineOfCode {
     pub diff: Diff,
     pub color: CodeColor,
     pub code: String,
+    /// what the plank says when it spawns, if it has to be fixed at the typewriter first
+    pub typo: Option<String>,
 }
 
 #[derive(AssetCollection)]
@@ -126,12 +140,74 @@ pub enum LocType {
     Red,
 }
 
+impl LocType {
+    /// tint of a plank painted this type
+    pub fn color(&self) -> Color {
+        match self {
+            LocType::Neutral => Color::default(),
+            LocType::Green => Color::GREEN,
+            LocType::Red => Color::RED,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Component)]
 pub struct LoCBlock {
     pub line_of_code: String,
     pub loc_type: LocType,
 }
 
+/// The text entity drawn onto a plank's label
+#[derive(Component)]
+pub struct PlankLabel(pub Entity);
+
+impl PlankLabel {
+    /// Planks in a bundle get their position in it written in front of their code,
+    /// starting from 1 at the top, which is the order the scan desk reads them in
+    ///
+    /// Only planks whose bundle changed, that left a bundle or whose code was edited are relabeled
+    fn number_planks(
+        changed_bundles: Query<&OrderedChildren, Changed<OrderedChildren>>,
+        left_bundle: RemovedComponents<Parent>,
+        edited: Query<(), Changed<LineOfCode>>,
+        bundles: Query<&OrderedChildren>,
+        planks: Query<(Entity, &PlankLabel)>,
+        mut labels: Query<(&LineOfCode, &mut TextSprite)>,
+    ) {
+        let mut relabel = changed_bundles
+            .iter()
+            .flat_map(|bundle| bundle.0.iter().copied())
+            .chain(left_bundle.iter())
+            .collect::<HashSet<_>>();
+        relabel.extend(
+            planks
+                .iter()
+                .filter(|(_, label)| edited.contains(label.0))
+                .map(|(plank, _)| plank),
+        );
+        if relabel.is_empty() {
+            return;
+        }
+        let numbers = bundles
+            .iter()
+            .flat_map(|bundle| bundle.0.iter().enumerate().map(|(i, e)| (*e, i + 1)))
+            .collect::<HashMap<_, _>>();
+        for plank in relabel {
+            let (plank, label) = unresult_or_continue!(planks.get(plank));
+            let (loc, mut text_sprite) = unresult_or_continue!(labels.get_mut(label.0));
+            let text = match numbers.get(&plank) {
+                Some(n) => format!("{}{n}.{} {}", ansi::YELLOW, ansi::RESET, loc.code),
+                None => loc.code.clone(),
+            };
+            let numbered = TextSprite::new(text, text_sprite.atlas.clone(), text_sprite.scale);
+            // only touch it when it changed, or the mesh gets rebuilt every frame
+            if numbered.text != text_sprite.text {
+                *text_sprite = numbered.with_layout(text_sprite.layout);
+            }
+        }
+    }
+}
+
 /// Spawn a separate camera
 #[derive(Bundle)]
 pub struct LoCSpriteBundle {
@@ -145,34 +221,75 @@ pub struct LoCSpriteBundle {
 pub const CODE_SPRITE_OFFSET: Vec3 = Vec3::from_array([0., -2000., 0.]);
 pub const CODE_LINE_LENGTH: usize = 60;
 pub const SCALE: f32 = 1.;
+/// half the size of a single plank, its label runs along z
+const PLANK_HALF_EXTENTS: Vec3 = Vec3::from_array([0.05, 0.015, 0.75]);
+
+/// the shape of a single plank, loose or in a bundle
+pub fn plank_collider() -> Collider {
+    Collider::cuboid(
+        PLANK_HALF_EXTENTS.x,
+        PLANK_HALF_EXTENTS.y,
+        PLANK_HALF_EXTENTS.z,
+    )
+}
 
-fn spawn_level(
-    mut commands: Commands,
-    levels: Res<Levels>,
-    font: Res<FontAtlas>,
-    locscene: Res<LoCScene>,
-    locations: Res<SceneLocations>,
-    mut images: ResMut<Assets<Image>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-) {
-    let pad_w = 40;
-    let pad_h = 60;
+fn spawn_level(levels: Res<Levels>, locations: Res<SceneLocations>, mut spawner: PlankSpawner) {
     let mut mdl_trans = *locations.locations.get("point3d_spawn").unwrap();
     mdl_trans.rotate_local_y(1.57);
     mdl_trans.translation.y += 0.2;
     let mut locs = levels.levels[levels.current].code.clone();
     locs.shuffle(&mut ThreadRng::default());
     for (i, loc) in locs.into_iter().enumerate() {
-        let mut text_sprite = TextSprite::new(loc.code.clone(), font.atlas.clone(), SCALE);
-        let mut text = commands.spawn();
+        let mut this_mdl_trans = mdl_trans;
+        this_mdl_trans.translation.z += i as f32 * 0.15;
+        spawner.spawn(&loc, LocType::Neutral, i, this_mdl_trans);
+    }
+}
+
+#[derive(SystemParam)]
+pub struct PlankSpawner<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub images: ResMut<'w, Assets<Image>>,
+    pub materials: ResMut<'w, Assets<StandardMaterial>>,
+    pub font: Res<'w, FontAtlas>,
+    pub locscene: Res<'w, LoCScene>,
+}
+
+impl<'w, 's> PlankSpawner<'w, 's> {
+    /// Spawns a plank with `loc` rendered onto it
+    /// `slot` is where its label goes on the 2D canvas,
+    /// no two planks alive at the same time can share one
+    pub fn spawn(
+        &mut self,
+        loc: &LineOfCode,
+        loc_type: LocType,
+        slot: usize,
+        trans: Transform,
+    ) -> Entity {
+        let pad_w = 40;
+        let pad_h = 60;
+        // the label only knows what's written on it, typo or not
+        let loc = LineOfCode {
+            code: loc.typo.clone().unwrap_or_else(|| loc.code.clone()),
+            typo: None,
+            ..loc.clone()
+        };
+        // code is broken exactly where it runs off the plank, not at spaces
+        let text_sprite = TextSprite::new(loc.code.clone(), self.font.atlas.clone(), SCALE)
+            .with_layout(TextLayout {
+                max_cols: Some(CODE_LINE_LENGTH),
+                wrap: Wrap::Hard,
+                align: Align::Centre,
+            });
+        let mut text = self.commands.spawn();
         let pos = CODE_SPRITE_OFFSET
             + Vec3::new(
                 0.0,
-                (ATLAS_CHAR_H * SCALE * 2.0 + pad_h as f32 * 0.5) * -(i as f32),
+                (ATLAS_CHAR_H * SCALE * 2.0 + pad_h as f32 * 0.5) * -(slot as f32),
                 0.0,
             );
-        text.add_children(|builder| text_sprite.spawn_chars(builder, |_| {}, 0));
 
+        let label = text.id();
         text.insert_bundle(LoCSpriteBundle {
             loc: loc.clone(),
             text: TextSpriteBundle {
@@ -206,7 +323,7 @@ fn spawn_level(
         // fill image.data with zeroes
         image.resize(size);
 
-        let image_handle = images.add(image);
+        let image_handle = self.images.add(image);
 
         let camera_trans = Transform::from_translation(Vec3::new(
             pos.x + ((CODE_LINE_LENGTH as f32 * 0.5 - 0.5) * ATLAS_CHAR_W * SCALE)
@@ -215,7 +332,7 @@ fn spawn_level(
             0.0,
         ));
 
-        commands
+        self.commands
             .spawn_bundle(Camera2dBundle {
                 camera: Camera {
                     priority: -1,
@@ -231,28 +348,26 @@ fn spawn_level(
             .insert(UiCameraConfig { show_ui: false })
             .insert(LoCEntity);
 
-        // let mut this_mdl_trans = mdl_trans.with_scale(Vec3::new(0.05, 0.015, 0.75));
-        let mut this_mdl_trans = mdl_trans.with_scale(Vec3::ONE);
-        this_mdl_trans.translation.z += i as f32 * 0.15;
         // spawn the mesh
-        commands
+        self.commands
             .spawn_bundle(PbrBundle {
-                mesh: locscene.gltf.clone(),
-                material: materials.add(StandardMaterial {
+                mesh: self.locscene.gltf.clone(),
+                material: self.materials.add(StandardMaterial {
+                    base_color: loc_type.color(),
                     base_color_texture: Some(image_handle.clone()),
                     ..Default::default()
                 }),
-                transform: this_mdl_trans,
+                transform: trans.with_scale(Vec3::ONE),
                 ..Default::default()
             })
             .insert(LoCBlock {
                 line_of_code: loc.code.clone(),
-                loc_type: LocType::Neutral,
+                loc_type,
             })
             .insert_bundle(PhysicsBundle {
                 body: RigidBody::Dynamic,
                 collider: ColliderBundle {
-                    collider: Collider::cuboid(0.05, 0.015, 0.75),
+                    collider: plank_collider(),
                     groups: ActiveCollisionTypes::all(),
                     ..Default::default()
                 },
@@ -261,6 +376,8 @@ fn spawn_level(
             })
             .insert(ActiveEvents::COLLISION_EVENTS)
             .insert(Interactable::LOC)
-            .insert(LoCEntity);
+            .insert(PlankLabel(label))
+            .insert(LoCEntity)
+            .id()
     }
 }
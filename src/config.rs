@@ -15,15 +15,40 @@ macro_rules! impl_plugin_config {
 // also add one to macro below
 pub struct ConfigPlugin {
     player: PlayerConfig,
+    score: ScoreConfig,
 }
 
-impl_plugin_config!(player);
+impl_plugin_config!(player, score);
 
 #[derive(Debug, Clone)]
 pub struct PlayerConfig {
     pub mouse_sens: f32,
     pub mvmnt_speed: f32,
     pub reach_dist: f32,
+    /// radians a second the camera turns with the look stick all the way over
+    pub stick_sens: f32,
+    pub grab_mode: GrabMode,
+    /// impulse behind a fully charged throw, before the item and upgrade multipliers
+    pub max_throw_impulse: f32,
+}
+
+/// How planks and bundles are carried
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GrabMode {
+    /// stuck to the camera, passing through everything
+    #[default]
+    ViewModel,
+    /// held out in front on a spring, bumping into the world and turnable with `RotateHeld`
+    Physics,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScoreConfig {
+    /// fraction of a job's score lost for every required line
+    /// still in the recycle bin when it's submitted
+    pub binned_line_penalty: f64,
+    /// seconds taken off the level timer every time the linter sorts a bundle
+    pub linter_cost_secs: u64,
 }
 
 pub const DEFAULT_CONFIG: ConfigPlugin = ConfigPlugin {
@@ -31,6 +56,13 @@ pub const DEFAULT_CONFIG: ConfigPlugin = ConfigPlugin {
         mouse_sens: 0.00012,
         mvmnt_speed: 800.,
         reach_dist: 3.,
+        stick_sens: 2.5,
+        grab_mode: GrabMode::ViewModel,
+        max_throw_impulse: 0.08,
+    },
+    score: ScoreConfig {
+        binned_line_penalty: 0.1,
+        linter_cost_secs: 20,
     },
 };
 
@@ -0,0 +1,113 @@
+use crate::{
+    code::LoCBlock,
+    player::interactions::OrderedChildren,
+    prelude::{phys::*, *},
+};
+
+pub struct ConveyorPlugin;
+
+impl Plugin for ConveyorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Conveyor::carry.run_in_state(GameState::InOffice));
+    }
+}
+
+/// A belt that drags planks and bundles resting on it along `velocity`
+///
+/// named `conveyor_<dir>_<speed>_<name>` in the glTF, where `<dir>` is one of
+/// `px`, `nx`, `pz`, `nz` (world axes) and `<speed>` is in m/s
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Conveyor {
+    pub velocity: Vec3,
+}
+
+impl Conveyor {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut sections = name.strip_prefix("conveyor_")?.split('_');
+        let dir = match sections.next()? {
+            "px" => Vec3::X,
+            "nx" => -Vec3::X,
+            "pz" => Vec3::Z,
+            "nz" => -Vec3::Z,
+            _ => return None,
+        };
+        let speed = sections.next()?.parse::<f32>().ok()?;
+        Some(Self {
+            velocity: dir * speed,
+        })
+    }
+
+    fn carry(
+        mut commands: Commands,
+        rapier: Res<RapierContext>,
+        conveyors: Query<(Entity, &Conveyor)>,
+        mut riders: Query<
+            (&RigidBody, Option<&mut Velocity>),
+            Or<(With<LoCBlock>, With<OrderedChildren>)>,
+        >,
+    ) {
+        for (belt, conveyor) in conveyors.iter() {
+            for contact in rapier.contacts_with(belt) {
+                if !contact.has_any_active_contacts() {
+                    continue;
+                }
+                let other = if contact.collider1() == belt {
+                    contact.collider2()
+                } else {
+                    contact.collider1()
+                };
+                let (body, vel) = match riders.get_mut(other) {
+                    Ok(r) => r,
+                    Err(_) => continue,
+                };
+                if *body != RigidBody::Dynamic {
+                    continue;
+                }
+                // keep gravity's say in the vertical, the belt only drives the horizontal
+                match vel {
+                    Some(mut vel) => {
+                        vel.linvel.x = conveyor.velocity.x;
+                        vel.linvel.z = conveyor.velocity.z;
+                    }
+                    None => {
+                        commands
+                            .entity(other)
+                            .insert(Velocity::linear(conveyor.velocity));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        let velocity = |name| Conveyor::from_name(name).map(|c| c.velocity);
+        assert_eq!(velocity("conveyor_px_1"), Some(Vec3::X));
+        assert_eq!(velocity("conveyor_nz_0.5_desk"), Some(Vec3::Z * -0.5));
+        assert_eq!(velocity("conveyor_nx_2_a_b"), Some(Vec3::X * -2.0));
+    }
+
+    #[test]
+    fn malformed_names() {
+        for name in [
+            "conveyor_px",
+            "conveyor_px_fast",
+            "conveyor__1",
+            "conveyor_py_1",
+        ] {
+            assert!(Conveyor::from_name(name).is_none(), "{name}");
+        }
+    }
+
+    #[test]
+    fn other_names() {
+        for name in ["", "conveyor", "sensor_px_1", "belt_px_1"] {
+            assert!(Conveyor::from_name(name).is_none(), "{name}");
+        }
+    }
+}
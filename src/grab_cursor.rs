@@ -17,10 +17,11 @@ fn cursor_grab(
     mut windows: ResMut<Windows>,
     btn: Res<Input<MouseButton>>,
     key: Res<Input<KeyCode>>,
+    pad: Res<Input<GamepadButton>>,
 ) {
     let window = windows.get_primary_mut().unwrap();
 
-    if btn.just_pressed(MouseButton::Left) {
+    if btn.just_pressed(MouseButton::Left) || pad.get_just_pressed().next().is_some() {
         set_cursor_grabbed(window, true);
     }
 
@@ -1,7 +1,7 @@
 use crate::collider::PhysicsBundle;
 use crate::prelude::*;
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub enum InteractableType {
     Hammer,
     LineOfCode,
@@ -38,7 +38,7 @@ impl Interactable {
         let name = name.strip_prefix("interactable_").unwrap();
         use InteractableType::*;
         let kind = match name {
-            "terminal" => Terminal,
+            name if name.starts_with("terminal") => Terminal,
             other => panic!("interactible not regognised {other}"),
         };
         Self { itype: kind }
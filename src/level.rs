@@ -1,6 +1,7 @@
 use crate::{
     code::{Diff, LineOfCode, LoCBlock},
     prelude::*,
+    shop::Upgrades,
     tools::{SType, SensorEvent},
     ui::TimerText,
 };
@@ -24,11 +25,18 @@ impl NewLevel {
     }
 }
 
+/// Sent whenever a job gets submitted at a terminal
+pub struct JobResult {
+    pub level: usize,
+    pub passed: bool,
+}
+
 pub struct LevelPlugin;
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<NewLevel>()
+            .add_event::<JobResult>()
             .init_resource::<LevelTimer>()
             .init_resource::<Levels>()
             .init_resource::<Submitted>()
@@ -49,7 +57,8 @@ fn start_first_level(mut next_level: EventWriter<NewLevel>, mut timer: ResMut<Le
 #[derive(Debug, Clone)]
 pub struct Levels {
     pub levels: Vec<CodeBlock>,
-    pub code_text: Vec<&'static str>,
+    /// the diff of each level, without its options
+    pub code_text: Vec<String>,
     pub current: usize,
 }
 
@@ -64,21 +73,53 @@ impl Levels {
         let levels = LEVELS.split(LEVEL_SEP).map(CodeBlock::from_str).collect();
         Self {
             levels,
-            code_text: LEVELS.split(LEVEL_SEP).collect(),
+            code_text: LEVELS
+                .split(LEVEL_SEP)
+                .map(|lvl| {
+                    lvl.lines()
+                        .filter(|ln| !ln.starts_with(OPTION_PREFIX))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .collect(),
             current: 0,
         }
     }
 }
 
+/// Lines starting with this set an option for the level instead of being code
+/// e.g. `@copies 2` or `@typo ...`
+const OPTION_PREFIX: &str = "@";
+
 #[derive(Debug, Clone)]
 pub struct CodeBlock {
     pub code: Vec<LineOfCode>,
+    /// how many planks the duplicator may make, `None` for no limit
+    pub max_copies: Option<usize>,
 }
 
 impl CodeBlock {
     fn from_str(s: &str) -> Self {
         let mut lines_of_code = Vec::new();
+        let mut max_copies = None;
         for ln in s.lines() {
+            if let Some(opt) = ln.strip_prefix(OPTION_PREFIX) {
+                let (name, arg) = opt.split_once(' ').unwrap_or((opt, ""));
+                match name {
+                    "copies" => {
+                        max_copies = Some(arg.trim().parse().expect("@copies takes a number"));
+                    }
+                    // e.g. `@typo pritn("hi")` after `++ print("hi")`
+                    "typo" => {
+                        let loc = lines_of_code
+                            .last_mut()
+                            .expect("@typo has to come after the line it applies to");
+                        loc.typo = Some(arg.to_owned());
+                    }
+                    _ => panic!("level option not recognised: {ln}"),
+                }
+                continue;
+            }
             let diff = Diff::from_line(ln);
             let code = ln.strip_prefix(&format!("{} ", diff.prefix())).unwrap();
 
@@ -86,10 +127,12 @@ impl CodeBlock {
                 color: diff.to_color(),
                 code: code.to_owned(),
                 diff,
+                typo: None,
             })
         }
         CodeBlock {
             code: lines_of_code,
+            max_copies,
         }
     }
 }
@@ -141,6 +184,21 @@ impl LevelTimer {
         self.time.duration()
     }
 
+    pub fn elapsed(&self) -> Duration {
+        self.time.elapsed()
+    }
+
+    /// gives the current job more time
+    pub fn extend(&mut self, time: Duration) {
+        let duration = self.time.duration() + time;
+        self.time.set_duration(duration);
+    }
+
+    /// uses up part of the remaining time, e.g. as the price of a station
+    pub fn spend(&mut self, time: Duration) {
+        self.time.tick(time);
+    }
+
     pub fn time_left(&self) -> u128 {
         (self.duration() - self.time.elapsed()).as_millis()
     }
@@ -149,11 +207,15 @@ impl LevelTimer {
         text.single_mut().sections[0].value = format!("ASSEMBLE DIFFS: {}", timer.remaining());
     }
 
-    pub fn new_level(mut timer: ResMut<LevelTimer>, mut new: EventReader<NewLevel>) {
+    pub fn new_level(
+        mut timer: ResMut<LevelTimer>,
+        mut new: EventReader<NewLevel>,
+        upgrades: Res<Upgrades>,
+    ) {
         if let Some(n) = new.iter().next() {
             timer
                 .time
-                .set_duration(Duration::from_secs(LEVEL_TIMES[n.number]));
+                .set_duration(Duration::from_secs(LEVEL_TIMES[n.number]) + upgrades.extra_time());
             timer.time.reset();
         }
     }
@@ -0,0 +1,266 @@
+use crate::{
+    level::{JobResult, LevelTimer, NewLevel},
+    prelude::*,
+    terminal::{ansi, TermWrite},
+    ui::MailText,
+};
+
+const MAIL: &str = include_str!("../assets/code/mail.txt");
+const MAIL_SEP: &str = "NEXT_MAIL\n";
+
+pub struct MailPlugin;
+
+impl Plugin for MailPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Inbox>()
+            .add_enter_system(GameState::InOffice, Inbox::reset)
+            .add_system(Inbox::deliver.run_in_state(GameState::InOffice))
+            .add_system(Inbox::update_ui.run_in_state(GameState::InOffice));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailTrigger {
+    /// `level n`: job `n` starts
+    Level(usize),
+    /// `pass` or `pass n`: any job, or job `n`, gets accepted
+    Pass(Option<usize>),
+    /// `fail` or `fail n`: any job, or job `n`, gets rejected
+    Fail(Option<usize>),
+    /// `time n`: `n` seconds into any job
+    Time(u64),
+}
+
+impl MailTrigger {
+    fn from_str(s: &str) -> Self {
+        let mut words = s.split_whitespace();
+        let kind = words.next().unwrap_or_default();
+        let n = words
+            .next()
+            .map(|n| n.parse::<u64>().expect("mail trigger takes a number"));
+        match (kind, n) {
+            ("level", Some(n)) => Self::Level(n as usize),
+            ("pass", n) => Self::Pass(n.map(|n| n as usize)),
+            ("fail", n) => Self::Fail(n.map(|n| n as usize)),
+            ("time", Some(n)) => Self::Time(n),
+            _ => panic!("mail trigger not recognised: {s}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub trigger: MailTrigger,
+    pub from: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Mail {
+    /// `trigger:`, `from:` and `subject:` lines, in that order
+    /// everything after them is the body
+    fn from_str(s: &str) -> Self {
+        let mut lines = s.lines();
+        let mut header = |key: &str| {
+            lines
+                .next()
+                .and_then(|ln| ln.strip_prefix(key))
+                .unwrap_or_else(|| panic!("mail is missing `{key}` in:\n{s}"))
+                .trim()
+                .to_owned()
+        };
+        let trigger = MailTrigger::from_str(&header("trigger:"));
+        let from = header("from:");
+        let subject = header("subject:");
+        Self {
+            trigger,
+            from,
+            subject,
+            body: lines.collect::<Vec<_>>().join("\n"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Received {
+    /// index into `Inbox::mail`
+    pub mail: usize,
+    pub read: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Inbox {
+    pub mail: Vec<Mail>,
+    /// in the order it arrived, every mail arrives at most once
+    pub received: Vec<Received>,
+}
+
+impl Default for Inbox {
+    fn default() -> Self {
+        Self {
+            mail: MAIL.split(MAIL_SEP).map(Mail::from_str).collect(),
+            received: Vec::new(),
+        }
+    }
+}
+
+impl Inbox {
+    pub fn unread(&self) -> usize {
+        self.received.iter().filter(|r| !r.read).count()
+    }
+
+    /// what the `inbox` command prints
+    pub fn listing(&self) -> String {
+        if self.received.is_empty() {
+            return "inbox is empty".to_owned();
+        }
+        let mut out = format!(
+            "inbox: {} mail, {} unread",
+            self.received.len(),
+            self.unread()
+        );
+        for (i, received) in self.received.iter().enumerate() {
+            let mail = &self.mail[received.mail];
+            let marker = if received.read { ' ' } else { '*' };
+            out.push_str(&format!(
+                "\n{:>2} {marker} {}: {}",
+                i + 1,
+                mail.from,
+                mail.subject
+            ));
+        }
+        out.push_str("\nuse `read <n>` to open one");
+        out
+    }
+
+    /// what the `read <n>` command prints, `n` starts at 1
+    pub fn open(&mut self, n: usize) -> String {
+        let received = match n.checked_sub(1).and_then(|i| self.received.get_mut(i)) {
+            Some(r) => r,
+            None => return format!("no mail #{n}, use inbox to list mail"),
+        };
+        received.read = true;
+        let mail = &self.mail[received.mail];
+        format!(
+            "from: {}\nsubject: {}\n\n{}",
+            mail.from, mail.subject, mail.body
+        )
+    }
+
+    fn reset(mut inbox: ResMut<Inbox>) {
+        inbox.received.clear();
+    }
+
+    fn deliver(
+        mut inbox: ResMut<Inbox>,
+        mut new_level: EventReader<NewLevel>,
+        mut results: EventReader<JobResult>,
+        timer: Res<LevelTimer>,
+        mut term_write: EventWriter<TermWrite>,
+    ) {
+        let started = new_level.iter().map(|n| n.number).collect::<Vec<_>>();
+        let results = results
+            .iter()
+            .map(|r| (r.level, r.passed))
+            .collect::<Vec<_>>();
+        let elapsed = timer.elapsed().as_secs();
+
+        let mut arrived = 0;
+        for i in 0..inbox.mail.len() {
+            if inbox.received.iter().any(|r| r.mail == i) {
+                continue;
+            }
+            let fire = match inbox.mail[i].trigger {
+                MailTrigger::Level(n) => started.contains(&n),
+                MailTrigger::Pass(n) => results
+                    .iter()
+                    .any(|&(level, passed)| passed && n.map_or(true, |n| n == level)),
+                MailTrigger::Fail(n) => results
+                    .iter()
+                    .any(|&(level, passed)| !passed && n.map_or(true, |n| n == level)),
+                MailTrigger::Time(secs) => timer.active && elapsed >= secs,
+            };
+            if fire {
+                inbox.received.push(Received {
+                    mail: i,
+                    read: false,
+                });
+                arrived += 1;
+            }
+        }
+
+        if arrived > 0 {
+            term_write.send(TermWrite {
+                s: format!(
+                    "\n{}{arrived} new mail, use inbox to read{}\n>>",
+                    ansi::YELLOW,
+                    ansi::RESET
+                ),
+            });
+        }
+    }
+
+    fn update_ui(inbox: Res<Inbox>, mut text: Query<&mut Text, With<MailText>>) {
+        let unread = inbox.unread();
+        text.single_mut().sections[0].value = match unread {
+            0 => String::new(),
+            n => format!("UNREAD MAIL: {n}"),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers() {
+        use MailTrigger::*;
+        assert_eq!(MailTrigger::from_str("level 2"), Level(2));
+        assert_eq!(MailTrigger::from_str("pass"), Pass(None));
+        assert_eq!(MailTrigger::from_str("pass 0"), Pass(Some(0)));
+        assert_eq!(MailTrigger::from_str("fail"), Fail(None));
+        assert_eq!(MailTrigger::from_str(" fail  3 "), Fail(Some(3)));
+        assert_eq!(MailTrigger::from_str("time 60"), Time(60));
+    }
+
+    #[test]
+    #[should_panic(expected = "not recognised")]
+    fn unknown_trigger() {
+        MailTrigger::from_str("weekday 3");
+    }
+
+    #[test]
+    #[should_panic(expected = "not recognised")]
+    fn trigger_missing_its_number() {
+        MailTrigger::from_str("level");
+    }
+
+    #[test]
+    #[should_panic(expected = "takes a number")]
+    fn trigger_with_a_bad_number() {
+        MailTrigger::from_str("time soon");
+    }
+
+    #[test]
+    fn mail() {
+        let mail = Mail::from_str(
+            "trigger: pass 1\nfrom: boss@git.co\nsubject: Hi\nfirst line\n\nlast line",
+        );
+        assert_eq!(mail.trigger, MailTrigger::Pass(Some(1)));
+        assert_eq!(mail.from, "boss@git.co");
+        assert_eq!(mail.subject, "Hi");
+        assert_eq!(mail.body, "first line\n\nlast line");
+    }
+
+    #[test]
+    #[should_panic(expected = "missing `subject:`")]
+    fn mail_missing_a_header() {
+        Mail::from_str("trigger: pass\nfrom: boss@git.co\nfirst line");
+    }
+
+    #[test]
+    fn shipped_mail_parses() {
+        assert!(!Inbox::default().mail.is_empty());
+    }
+}
@@ -9,20 +9,28 @@ use bevy_rapier3d::plugin::RapierPhysicsPlugin;
 
 mod asset;
 mod audio;
+mod bindings;
 mod code;
 mod collider;
 mod config;
+mod conveyor;
 mod debug;
 mod grab_cursor;
 mod interactable;
 mod level;
+mod mail;
 mod office;
 mod player;
 mod prelude;
+mod rack;
+mod recycle;
 mod score;
+mod shop;
 mod state;
 mod terminal;
 mod tools;
+mod tutorial;
+mod typewriter;
 mod ui;
 mod utils;
 mod viewmodel;
@@ -53,6 +61,7 @@ fn main() {
         .insert_resource(PointLightShadowMap { size: 512 })
         .insert_resource(TotalPoints { sum: 0., total: 0. })
         .add_plugins(DefaultPlugins)
+        .add_plugin(bindings::BindingsPlugin)
         .add_plugins(debug::DebugPlugins)
         .add_plugin(terminal::TerminalPlugin)
         .add_plugin(office::OfficePlugin)
@@ -62,6 +71,13 @@ fn main() {
         .add_plugin(ui::UiPlugin)
         .add_plugin(code::CodePlugin)
         .add_plugin(tools::ToolsPlugin)
+        .add_plugin(recycle::RecyclePlugin)
+        .add_plugin(conveyor::ConveyorPlugin)
+        .add_plugin(rack::RackPlugin)
+        .add_plugin(mail::MailPlugin)
+        .add_plugin(tutorial::TutorialPlugin)
+        .add_plugin(typewriter::TypewriterPlugin)
+        .add_plugin(shop::ShopPlugin)
         .add_plugin(AudioPlugin)
         .add_plugin(audio::SusdioPlugin);
     app.run();
@@ -85,6 +85,8 @@ pub enum OfficeAssetKind {
     Collider,
     Sensor,
     Dynamic,
+    Conveyor,
+    Rack,
     Interactable,
     Point3D,
     RenderTarget,
@@ -99,6 +101,8 @@ impl OfficeAssetKind {
             Collider,
             Sensor,
             Dynamic,
+            Conveyor,
+            Rack,
             Point3D,
             RenderTarget,
             EmissiveNormal,
@@ -116,6 +120,8 @@ impl OfficeAssetKind {
             Collider => "collider_",
             Sensor => "sensor_",
             Dynamic => "dynamic_",
+            Conveyor => "conveyor_",
+            Rack => "rack_",
             Interactable => "interactable_",
             Point3D => "point3d_",
             RenderTarget => "render_target_",
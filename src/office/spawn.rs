@@ -1,9 +1,12 @@
 use super::{OfficeAssetBuilder, OfficeAssetKind, OfficeAssets, OfficeEntities};
 use crate::collider::{ColliderBundle, PhysicsBundle};
+use crate::conveyor::Conveyor;
 use crate::interactable::Interactable;
 use crate::office::SceneLocations;
 use crate::phys::group::collide::{all, sensor, static_body};
 use crate::prelude::{phys::*, utils::*, *};
+use crate::rack::Rack;
+use crate::tools::ToolKind;
 use bevy::ecs::system::SystemParam;
 use bevy::gltf::{Gltf, GltfMesh, GltfNode};
 use std::f32::consts::FRAC_PI_4;
@@ -221,6 +224,8 @@ pub fn spawn_office(
             Sensor => spawn_sensor(&mut commands, name, builder, &lookup),
             Interactable => spawn_interactable(&mut commands, name, builder, &lookup),
             Dynamic => spawn_dynamic(&mut commands, name, builder, &lookup, &default_material),
+            Conveyor => spawn_conveyor(&mut commands, name, builder, &lookup, &default_material),
+            Rack => spawn_rack(&mut commands, name, builder, &lookup, &default_material),
             Normal => spawn_normal(&mut commands, builder, &lookup, &default_material),
             // note to peng: i moved the Point3D loading somewhere else
             // because it really didn't need to be here
@@ -263,6 +268,81 @@ fn spawn_collider(
         .id()
 }
 
+fn spawn_conveyor(
+    commands: &mut Commands,
+    name: &str,
+    builder: &OfficeAssetBuilder,
+    lookup: &OfficeAssetsLookup,
+    default_material: &Handle<StandardMaterial>,
+) -> Entity {
+    // the belt is solid, it's drawn too since it's not part of the collider_ meshes
+    let entity = spawn_collider(commands, name, builder, lookup);
+    let mut belt = commands.entity(entity);
+    let mesh = lookup.gltf_mesh.get(&builder.mesh).unwrap();
+    belt.insert_bundle(PbrBundle {
+        mesh: mesh.primitives[0].mesh.clone(),
+        material: mesh.primitives[0]
+            .material
+            .clone()
+            .unwrap_or_else(|| default_material.clone()),
+        transform: builder.trans,
+        ..Default::default()
+    });
+    match Conveyor::from_name(name) {
+        Some(conveyor) => {
+            belt.insert(conveyor);
+        }
+        None => {
+            warn!("{name} should be named conveyor_<px|nx|pz|nz>_<speed>_<name>, it won't move")
+        }
+    }
+    entity
+}
+
+fn spawn_rack(
+    commands: &mut Commands,
+    name: &str,
+    builder: &OfficeAssetBuilder,
+    lookup: &OfficeAssetsLookup,
+    default_material: &Handle<StandardMaterial>,
+) -> Entity {
+    let entity = spawn_collider(commands, name, builder, lookup);
+    let mesh = lookup.gltf_mesh.get(&builder.mesh).unwrap();
+    // the slot_ empties are the node's children without a mesh
+    let slots = lookup
+        .gltf_nodes
+        .get(&builder.node)
+        .map(|node| {
+            node.children
+                .iter()
+                .filter(|child| child.mesh.is_none())
+                .map(|child| {
+                    builder
+                        .trans
+                        .mul_transform(child.transform)
+                        .with_scale(Vec3::ONE)
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if slots.is_empty() {
+        warn!("{name} has no slot_ children, nothing will snap to it");
+    }
+    commands
+        .entity(entity)
+        .insert_bundle(PbrBundle {
+            mesh: mesh.primitives[0].mesh.clone(),
+            material: mesh.primitives[0]
+                .material
+                .clone()
+                .unwrap_or_else(|| default_material.clone()),
+            transform: builder.trans,
+            ..Default::default()
+        })
+        .insert(Rack::new(slots));
+    entity
+}
+
 fn spawn_sensor(
     commands: &mut Commands,
     name: &str,
@@ -273,8 +353,8 @@ fn spawn_sensor(
         .mesh
         .get(&builder.collider_mesh.clone().unwrap())
         .unwrap();
-    commands
-        .spawn()
+    let mut sensor_commands = commands.spawn();
+    sensor_commands
         .insert(Collider::from_bevy_mesh(mesh, &ComputedColliderShape::TriMesh).unwrap())
         .insert(Sensor)
         .insert(ColliderType::Sensor)
@@ -284,8 +364,14 @@ fn spawn_sensor(
         })
         .insert(ActiveEvents::COLLISION_EVENTS)
         .insert(ActiveCollisionTypes::all())
-        .insert_bundle(TransformBundle::from_transform(builder.trans))
-        .id()
+        .insert_bundle(TransformBundle::from_transform(builder.trans));
+    match ToolKind::from_name(name) {
+        Some(kind) => {
+            sensor_commands.insert(kind);
+        }
+        None => warn!("{name} isn't a known tool, it won't do anything"),
+    }
+    sensor_commands.id()
 }
 
 fn spawn_interactable(
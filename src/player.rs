@@ -3,10 +3,14 @@ use crate::prelude::*;
 pub use interactions::{MouseInteraction, PlayerLookingAt};
 
 pub mod fsm;
+pub mod grab;
+pub mod hammer;
+pub mod hotbar;
 pub mod interactions;
 pub mod look;
 pub mod movement;
 pub mod spawn;
+pub mod throw;
 
 pub struct PlayerPlugin;
 
@@ -16,6 +20,10 @@ impl Plugin for PlayerPlugin {
         movement::build(app);
         look::build(app);
         interactions::build(app);
+        hammer::build(app);
+        hotbar::build(app);
+        grab::build(app);
+        throw::build(app);
         app.insert_resource(PlayerStateMachine::default());
     }
 }
@@ -0,0 +1,223 @@
+use crate::audio::events::{InteractSoundEvent, InteractSoundType};
+use crate::bindings::{Action, Actions};
+use crate::{
+    config::PlayerConfig,
+    interactable::Interactable,
+    phys::group::collide::{dynamic_body, interactable_dynamic_body},
+    player::{
+        fsm::{PlayerState, PlayerStateMachine},
+        interactions::{InteractionSystemLabel, PendingInteraction},
+        throw::ThrowCharge,
+        PlayerCamera,
+    },
+    prelude::{phys::*, *},
+};
+use bevy::input::mouse::MouseMotion;
+use std::f32::consts::PI;
+
+/// how far in front of the camera grabbed things float
+const GRAB_DIST: f32 = 1.2;
+/// how hard a grabbed thing is pulled back to where it should be, per second
+const GRAB_STIFFNESS: f32 = 12.0;
+/// fastest a grabbed thing will move to catch up, so it can't tunnel through walls
+const GRAB_MAX_SPEED: f32 = 6.0;
+
+pub fn build(app: &mut App) {
+    app.init_resource::<PhysicsGrab>()
+        .add_system(
+            PhysicsGrab::release
+                .run_in_state(GameState::InOffice)
+                .run_unless_resource_equals(PlayerStateMachine::INTERACTING)
+                .before(InteractionSystemLabel::Resolve),
+        )
+        .add_system(PhysicsGrab::rotate.run_in_state(GameState::InOffice))
+        .add_system(PhysicsGrab::follow.run_in_state(GameState::InOffice));
+}
+
+/// A plank or bundle held out in front of the player by a spring, still colliding with the world.
+/// Only used with `GrabMode::Physics`
+#[derive(Default)]
+pub struct PhysicsGrab {
+    pub held: Option<Entity>,
+    /// what it turns back into once it's let go of
+    interactable: Option<Interactable>,
+    /// relative to the way the player is facing
+    rotation: Quat,
+}
+
+impl PhysicsGrab {
+    /// mouse look is paused while this is true
+    pub fn is_rotating(grab: Res<PhysicsGrab>, actions: Res<Actions>) -> bool {
+        grab.held.is_some() && actions.pressed(Action::RotateHeld)
+    }
+
+    /// what's held turns back into this once it's let go of
+    pub fn interactable(&self) -> Option<Interactable> {
+        self.interactable
+    }
+
+    /// Puts what's held back into the world, pushed by `impulse`
+    pub fn let_go(&mut self, commands: &mut Commands, impulse: Vec3) {
+        let held = match self.held {
+            Some(e) => e,
+            None => return,
+        };
+        let mut held_commands = commands.entity(held);
+        held_commands
+            .insert(interactable_dynamic_body())
+            .insert(GravityScale(1.0))
+            .insert(ExternalImpulse {
+                impulse,
+                ..Default::default()
+            });
+        if let Some(interactable) = self.interactable {
+            held_commands.insert(interactable);
+        }
+        *self = PhysicsGrab::default();
+    }
+
+    /// the way the camera faces, ignoring pitch
+    fn facing(camera: &Transform) -> Quat {
+        let fwd = camera.rotation * -Vec3::Z;
+        Quat::from_rotation_y(f32::atan2(-fwd.x, -fwd.z))
+    }
+
+    pub fn grab(
+        mut commands: Commands,
+        mut grab: ResMut<PhysicsGrab>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        pending: Res<PendingInteraction>,
+        camera_query: Query<&Transform, With<PlayerCamera>>,
+        items: Query<(&Interactable, &Transform)>,
+    ) {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
+        let (interactable, trans) = match items.get(event.with) {
+            Ok(i) => i,
+            Err(_) => return,
+        };
+        let facing = Self::facing(camera_query.single());
+        *grab = PhysicsGrab {
+            held: Some(event.with),
+            interactable: Some(*interactable),
+            rotation: facing.inverse() * trans.rotation,
+        };
+        // out of sight of the interaction ray and the tool sensors until it's let go,
+        // and loose again if it was sitting in a rack
+        commands
+            .entity(event.with)
+            .remove::<Interactable>()
+            .insert(RigidBody::Dynamic)
+            .insert(dynamic_body())
+            .insert(GravityScale(0.0))
+            .insert(Velocity::zero());
+        player_state.change_state(PlayerState::Holding);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Pickup,
+        });
+    }
+
+    /// `Interact` lets go where it is, `Throw` winds up a throw that `ThrowCharge` lets go of.
+    /// Whatever was pressed is used up here so it doesn't also pick something else up,
+    /// and `Swap` is always used up while something's held
+    fn release(
+        mut commands: Commands,
+        mut grab: ResMut<PhysicsGrab>,
+        mut charge: ResMut<ThrowCharge>,
+        mut actions: ResMut<Actions>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+        mut player_state: ResMut<PlayerStateMachine>,
+    ) {
+        let held = match grab.held {
+            Some(e) => e,
+            None => return,
+        };
+        // swapping and pulling a plank out of a bundle need empty hands,
+        // and the spring counts as a full one
+        actions.consume(Action::Swap);
+        let throw = actions.just_pressed(Action::Throw);
+        let drop = actions.just_pressed(Action::Interact);
+        if !throw && !drop {
+            return;
+        }
+        actions.clear_just_pressed();
+        if throw {
+            charge.begin(held);
+        } else if drop {
+            grab.let_go(&mut commands, Vec3::ZERO);
+            player_state.change_state(PlayerState::Idle);
+            interact_sfx_event.send(InteractSoundEvent {
+                int_type: InteractSoundType::Attach,
+            });
+        }
+    }
+
+    /// `RotateHeld` + mouse or the look stick turns what's held,
+    /// at the same sensitivity as looking around
+    fn rotate(
+        mut grab: ResMut<PhysicsGrab>,
+        mut motion: EventReader<MouseMotion>,
+        actions: Res<Actions>,
+        config: Res<PlayerConfig>,
+        windows: Res<Windows>,
+        time: Res<Time>,
+    ) {
+        if grab.held.is_none() || !actions.pressed(Action::RotateHeld) {
+            return;
+        }
+        let stick = actions.look * config.stick_sens * time.delta_seconds();
+        grab.rotation =
+            (Quat::from_rotation_y(stick.x) * Quat::from_rotation_x(-stick.y) * grab.rotation)
+                .normalize();
+        let window = windows.get_primary().unwrap();
+        let window_scale = window.height().min(window.width());
+        for ev in motion.iter() {
+            let yaw = (config.mouse_sens * ev.delta.x * window_scale).to_radians();
+            let pitch = (config.mouse_sens * ev.delta.y * window_scale).to_radians();
+            let turn = Quat::from_rotation_y(yaw) * Quat::from_rotation_x(pitch);
+            grab.rotation = (turn * grab.rotation).normalize();
+        }
+    }
+
+    /// Pulls what's held towards its spot in front of the camera
+    fn follow(
+        mut grab: ResMut<PhysicsGrab>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        camera_query: Query<&Transform, With<PlayerCamera>>,
+        mut items: Query<(&Transform, Option<&mut Velocity>), Without<PlayerCamera>>,
+    ) {
+        let held = match grab.held {
+            Some(e) => e,
+            None => return,
+        };
+        let (trans, vel) = match items.get_mut(held) {
+            Ok(i) => i,
+            // e.g. the level restarted
+            Err(_) => {
+                *grab = PhysicsGrab::default();
+                player_state.change_state(PlayerState::Idle);
+                return;
+            }
+        };
+        // inserted by `grab`, not there until its commands are applied
+        let mut vel = match vel {
+            Some(v) => v,
+            None => return,
+        };
+        let camera = camera_query.single();
+        let target = camera.translation + camera.rotation * -Vec3::Z * GRAB_DIST;
+        vel.linvel =
+            ((target - trans.translation) * GRAB_STIFFNESS).clamp_length_max(GRAB_MAX_SPEED);
+
+        let target_rot = Self::facing(camera) * grab.rotation;
+        let (axis, mut angle) = (target_rot * trans.rotation.inverse()).to_axis_angle();
+        // the short way round
+        if angle > PI {
+            angle -= 2.0 * PI;
+        }
+        vel.angvel = axis * angle * GRAB_STIFFNESS;
+    }
+}
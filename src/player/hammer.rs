@@ -0,0 +1,189 @@
+use crate::audio::events::HammerSoundEvent;
+use crate::bindings::Action;
+use crate::{
+    interactable::{Interactable, InteractableType},
+    phys::group::collide::interactable_dynamic_body,
+    player::{interactions::AddInteractionRule, PlayerCamera},
+    prelude::{phys::*, *},
+    tools::HammerModel,
+    viewmodel::{ViewModel, ViewModelHold},
+};
+use rand::{prelude::SmallRng, Rng, SeedableRng};
+
+/// seconds from the start of one swing until the next one can start
+const SWING_COOLDOWN: f32 = 0.7;
+/// how far into the swing animation the head meets what it's aimed at,
+/// everything after is follow-through
+const SWING_IMPACT_FRACTION: f32 = 0.5;
+/// how far in front of the camera the hammer reaches
+const HAMMER_REACH: f32 = 1.5;
+/// radius of the ball swept along the swing to find what got hit
+const HAMMER_HEAD_RADIUS: f32 = 0.1;
+
+pub fn build(app: &mut App) {
+    use InteractableType::*;
+    app.init_resource::<HammerSwing>()
+        .add_interaction_rule(
+            Action::Swing,
+            &[ViewModelHold::Hammer],
+            &[None, Some(LineOfCode), Some(LineOfCodeGlobule)],
+            HammerSwing::start,
+        )
+        .add_system(HammerSwing::tick.run_in_state(GameState::InOffice));
+}
+
+/// The hammer's swing, started on click and landing partway into the animation
+pub struct HammerSwing {
+    impact: Timer,
+    cooldown: Timer,
+    swinging: bool,
+}
+
+impl Default for HammerSwing {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(SWING_COOLDOWN, false);
+        // ready to swing straight away
+        cooldown.tick(cooldown.duration());
+        Self {
+            impact: Timer::default(),
+            cooldown,
+            swinging: false,
+        }
+    }
+}
+
+impl HammerSwing {
+    pub fn is_ready(&self) -> bool {
+        !self.swinging && self.cooldown.finished()
+    }
+
+    /// Plays the swing on the held hammer, what it hits is decided in `tick`
+    pub fn start(
+        mut swing: ResMut<HammerSwing>,
+        hammer: Res<HammerModel>,
+        clips: Res<Assets<AnimationClip>>,
+        viewmodel_query: Query<&Children, With<ViewModel>>,
+        mut animations: Query<&mut AnimationPlayer>,
+    ) {
+        let held = match viewmodel_query.get_single() {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        if !swing.is_ready() {
+            return;
+        }
+        let clip = match clips.get(&hammer.swing_animation) {
+            Some(c) => c,
+            None => return,
+        };
+
+        swing.swinging = true;
+        swing.impact = Timer::from_seconds(clip.duration() * SWING_IMPACT_FRACTION, false);
+        swing.cooldown.reset();
+        for e in held.iter() {
+            if let Ok(mut player) = animations.get_mut(*e) {
+                player.play(hammer.swing_animation.clone());
+            }
+        }
+    }
+
+    /// Lands the swing on whatever is in front of the camera at the moment of impact,
+    /// unless the hammer was put away before then
+    fn tick(
+        mut commands: Commands,
+        mut swing: ResMut<HammerSwing>,
+        mut sound_event: EventWriter<HammerSoundEvent>,
+        time: Res<Time>,
+        rapier: Res<RapierContext>,
+        camera_query: Query<&Transform, With<PlayerCamera>>,
+        viewmodel_query: Query<&ViewModel>,
+        interact_type: Query<&Interactable, Without<ViewModel>>,
+        transforms: Query<&Transform>,
+        children: Query<&Children>,
+    ) {
+        swing.cooldown.tick(time.delta());
+        if !swing.swinging {
+            return;
+        }
+        let holding = viewmodel_query.get_single().ok().map(|v| v.holding());
+        if holding != Some(ViewModelHold::Hammer) {
+            swing.swinging = false;
+            return;
+        }
+        if !swing.impact.tick(time.delta()).just_finished() {
+            return;
+        }
+        swing.swinging = false;
+
+        let camera_trans = camera_query.single();
+        let dir = camera_trans.rotation * -Vec3::Z;
+        let hit = rapier.cast_shape(
+            camera_trans.translation,
+            Quat::IDENTITY,
+            dir,
+            &Collider::ball(HAMMER_HEAD_RADIUS),
+            HAMMER_REACH,
+            group::interact::player_vision().into(),
+        );
+        let hit = hit.and_then(|(e, _)| interact_type.get(e).ok().map(|i| (e, i.itype())));
+        let landed = match hit {
+            Some((e, InteractableType::LineOfCode)) => {
+                Self::bump(&mut commands, e, dir);
+                true
+            }
+            Some((e, InteractableType::LineOfCodeGlobule)) => {
+                Self::smash(&mut commands, e, &transforms, &children);
+                true
+            }
+            _ => false,
+        };
+        sound_event.send(HammerSoundEvent { hit: landed });
+    }
+
+    /// knocks a lone plank away from the player
+    fn bump(commands: &mut Commands, plank: Entity, dir: Vec3) {
+        let dir_y_inv = Vec3::new(dir.x, -dir.y, dir.z);
+        commands.entity(plank).insert(ExternalImpulse {
+            impulse: dir_y_inv * 0.05,
+            ..Default::default()
+        });
+    }
+
+    /// breaks a bundle back up into loose planks
+    fn smash(
+        commands: &mut Commands,
+        bundle: Entity,
+        transforms: &Query<&Transform>,
+        children: &Query<&Children>,
+    ) {
+        let planks = match children.get(bundle) {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        let bundle_trans = match transforms.get(bundle) {
+            Ok(t) => *t,
+            Err(_) => return,
+        };
+        let mut random = SmallRng::from_entropy();
+        for (i, e) in planks.iter().enumerate() {
+            commands.entity(bundle).remove_children(&[*e]);
+            let random_x = random.gen_range(-1.0..=1.0);
+            let random_z = random.gen_range(-1.0..=1.0);
+            let force = Vec3::new(random_x, 1.0, random_z);
+            let mut new_trans = bundle_trans;
+            new_trans.translation.y += 0.02 * i as f32;
+            commands
+                .entity(*e)
+                .insert_bundle(TransformBundle::from_transform(new_trans))
+                .insert(ExternalImpulse {
+                    impulse: force * 0.015,
+                    ..Default::default()
+                })
+                .insert(Interactable::LOC)
+                .insert(RigidBody::Dynamic)
+                .insert(ActiveCollisionTypes::all())
+                .insert(interactable_dynamic_body());
+        }
+        commands.entity(bundle).despawn();
+    }
+}
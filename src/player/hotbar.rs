@@ -0,0 +1,176 @@
+use crate::{
+    bindings::{Action, Actions},
+    player::{
+        fsm::{PlayerState, PlayerStateMachine},
+        interactions::held_transform,
+    },
+    prelude::*,
+    ui::HotbarText,
+    viewmodel::{ViewModel, ViewModelHold},
+};
+use bevy::input::mouse::MouseWheel;
+
+pub const HOTBAR_SLOTS: usize = 3;
+const SLOT_ACTIONS: [Action; HOTBAR_SLOTS] = [Action::Slot1, Action::Slot2, Action::Slot3];
+
+pub fn build(app: &mut App) {
+    app.init_resource::<Hotbar>()
+        .add_enter_system(GameState::InOffice, Hotbar::reset)
+        .add_system(
+            Hotbar::switch
+                .run_in_state(GameState::InOffice)
+                .run_unless_resource_equals(PlayerStateMachine::INTERACTING),
+        )
+        .add_system(Hotbar::forget_despawned.run_in_state(GameState::InOffice))
+        .add_system(Hotbar::update_ui.run_in_state(GameState::InOffice));
+}
+
+/// Something carried in a slot that isn't the active one, hidden and out of the hand
+#[derive(Debug, Clone, Copy)]
+pub struct Stowed {
+    pub entity: Entity,
+    pub hold: ViewModelHold,
+}
+
+/// The active slot is whatever the `ViewModel` holds,
+/// the others keep their items stowed until they're switched to
+#[derive(Debug, Clone, Default)]
+pub struct Hotbar {
+    pub slots: [Option<Stowed>; HOTBAR_SLOTS],
+    pub active: usize,
+}
+
+impl Hotbar {
+    fn reset(mut hotbar: ResMut<Hotbar>) {
+        *hotbar = Hotbar::default();
+    }
+
+    /// The slot picked with the slot bindings or the scroll wheel, if it changed
+    fn selected(&self, actions: &Actions, wheel: &mut EventReader<MouseWheel>) -> Option<usize> {
+        let mut scrolled = wheel.iter().map(|ev| ev.y).sum::<f32>();
+        if actions.just_pressed(Action::PrevSlot) {
+            scrolled += 1.0;
+        }
+        if actions.just_pressed(Action::NextSlot) {
+            scrolled -= 1.0;
+        }
+        let picked = SLOT_ACTIONS
+            .iter()
+            .position(|a| actions.just_pressed(*a))
+            .or_else(|| match scrolled {
+                y if y > 0.0 => Some((self.active + HOTBAR_SLOTS - 1) % HOTBAR_SLOTS),
+                y if y < 0.0 => Some((self.active + 1) % HOTBAR_SLOTS),
+                _ => None,
+            })?;
+        (picked != self.active).then_some(picked)
+    }
+
+    /// Stows what's in hand and takes out whatever the new slot has
+    fn switch(
+        mut commands: Commands,
+        mut hotbar: ResMut<Hotbar>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        mut wheel: EventReader<MouseWheel>,
+        actions: Res<Actions>,
+        mut viewmodel_query: Query<(&mut ViewModel, Entity, Option<&Children>)>,
+        children: Query<&Children>,
+        mut visibility: Query<&mut Visibility>,
+    ) {
+        let next = match hotbar.selected(&actions, &mut wheel) {
+            Some(n) => n,
+            None => return,
+        };
+        let (mut viewmodel, vm_ent, vm_children) = match viewmodel_query.get_single_mut() {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+
+        let active = hotbar.active;
+        let held = vm_children.and_then(|c| c.get(0)).copied();
+        hotbar.slots[active] = match held {
+            Some(entity) if viewmodel.holding() != ViewModelHold::Empty => {
+                commands.entity(vm_ent).remove_children(&[entity]);
+                set_visible(entity, false, &children, &mut visibility);
+                Some(Stowed {
+                    entity,
+                    hold: viewmodel.holding(),
+                })
+            }
+            _ => None,
+        };
+
+        match hotbar.slots[next].take() {
+            Some(stowed) => {
+                set_visible(stowed.entity, true, &children, &mut visibility);
+                commands
+                    .entity(stowed.entity)
+                    .insert(held_transform(stowed.hold));
+                commands.entity(vm_ent).push_children(&[stowed.entity]);
+                viewmodel.change_holding(stowed.hold);
+                player_state.change_state(PlayerState::Holding);
+            }
+            None => {
+                viewmodel.change_holding(ViewModelHold::Empty);
+                player_state.change_state(PlayerState::Idle);
+            }
+        }
+        hotbar.active = next;
+    }
+
+    /// stowed planks go away with the rest of the level when it restarts
+    fn forget_despawned(mut hotbar: ResMut<Hotbar>, entities: Query<()>) {
+        for slot in hotbar.slots.iter_mut() {
+            if matches!(slot, Some(s) if !entities.contains(s.entity)) {
+                *slot = None;
+            }
+        }
+    }
+
+    fn update_ui(
+        hotbar: Res<Hotbar>,
+        viewmodel: Query<&ViewModel>,
+        mut text: Query<&mut Text, With<HotbarText>>,
+    ) {
+        let holding = viewmodel
+            .get_single()
+            .map(|vm| vm.holding())
+            .unwrap_or(ViewModelHold::Empty);
+        let slots = (0..HOTBAR_SLOTS)
+            .map(|i| {
+                let hold = match hotbar.slots[i] {
+                    _ if i == hotbar.active => holding,
+                    Some(stowed) => stowed.hold,
+                    None => ViewModelHold::Empty,
+                };
+                let name = match hold {
+                    ViewModelHold::Empty => "-",
+                    ViewModelHold::Hammer => "HAMMER",
+                    ViewModelHold::LoC => "PLANK",
+                    ViewModelHold::LoCBundle => "BUNDLE",
+                };
+                match i == hotbar.active {
+                    true => format!(">[{}] {name}<", i + 1),
+                    false => format!(" [{}] {name} ", i + 1),
+                }
+            })
+            .collect::<Vec<_>>();
+        text.single_mut().sections[0].value = slots.join(" ");
+    }
+}
+
+/// Visibility isn't inherited, so a bundle or the hammer's scene has to be hidden piece by piece
+fn set_visible(
+    entity: Entity,
+    visible: bool,
+    children: &Query<&Children>,
+    visibility: &mut Query<&mut Visibility>,
+) {
+    if let Ok(mut vis) = visibility.get_mut(entity) {
+        vis.is_visible = visible;
+    }
+    if let Ok(ch) = children.get(entity) {
+        for child in ch.iter() {
+            set_visible(*child, visible, children, visibility);
+        }
+    }
+}
@@ -1,24 +1,30 @@
-use crate::audio::events::{HammerSoundEvent, InteractSoundEvent, InteractSoundType};
-use crate::code::LoCEntity;
+use crate::audio::events::{InteractSoundEvent, InteractSoundType};
+use crate::bindings::{Action, Actions};
+use crate::code::{plank_collider, LoCEntity};
 use crate::{
     collider::{ColliderBundle, PhysicsBundle},
-    config::PlayerConfig,
+    config::{GrabMode, PlayerConfig},
     interactable::{Interactable, InteractableType},
     phys::group::collide::{interactable_dynamic_body, none},
     player::{
         fsm::{PlayerState, PlayerStateMachine},
+        grab::PhysicsGrab,
         PlayerCamera,
     },
     prelude::{phys::*, *},
+    terminal::ActiveTerminal,
+    utils::EName,
     viewmodel::{ViewModel, ViewModelHold},
 };
-use rand::{prelude::SmallRng, Rng, SeedableRng};
+use bevy::utils::HashMap;
 
+#[derive(Clone)]
 pub struct MouseInteraction {
-    button: MouseButton,
-    with: Entity,
-    direction: Vec3,
+    pub action: Action,
+    pub with: Entity,
     pub toi: f32,
+    /// where the ray hit `with`, in world space
+    pub point: Vec3,
 }
 
 #[derive(Default)]
@@ -28,174 +34,254 @@ pub struct PlayerLookingAt {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
-enum InteractionSystemLabel {
-    Loc,
-    LocBundle,
-    Hammer,
-    HoldingAny,
-    HoldingNone,
-    RightClick,
-    ClearRes,
+pub enum InteractionSystemLabel {
+    Detect,
+    Resolve,
 }
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Eq)]
-pub struct InteractSingleSystemLock {
-    pub ran: bool,
+/// What a click gets matched on: the action, what's in hand and what's being looked at.
+/// The target is `None` when looking at nothing that can be interacted with
+pub type InteractionKey = (Action, ViewModelHold, Option<InteractableType>);
+
+/// Which handler runs for each kind of click, filled in with `add_interaction_rule`
+#[derive(Default)]
+pub struct InteractionRules {
+    handlers: HashMap<InteractionKey, usize>,
+    count: usize,
 }
 
-impl InteractSingleSystemLock {
-    // pub fn can_u_run_owo(&self) -> bool {
-    //     !self.ran
-    // }
+/// This frame's click and the handler it resolved to, at most one per frame
+#[derive(Default)]
+pub struct PendingInteraction {
+    handler: Option<usize>,
+    event: Option<MouseInteraction>,
+}
 
-    pub fn i_ran_dawddy(&mut self) {
-        self.ran = true;
+impl PendingInteraction {
+    /// the click, `None` if it wasn't on anything
+    pub fn event(&self) -> Option<&MouseInteraction> {
+        self.event.as_ref()
+    }
+
+    /// `action` clicked on `with`, for running a handler by itself
+    #[cfg(test)]
+    pub fn clicked(action: Action, with: Entity) -> Self {
+        Self {
+            handler: None,
+            event: Some(MouseInteraction {
+                action,
+                with,
+                toi: 0.0,
+                point: Vec3::ZERO,
+            }),
+        }
     }
 }
 
+pub trait AddInteractionRule {
+    /// Runs `handler` when `action` is pressed while holding any of `holding`
+    /// and looking at any of `targets`. Every key can only have one handler
+    fn add_interaction_rule<P, S: IntoConditionalSystem<P>>(
+        &mut self,
+        action: Action,
+        holding: &[ViewModelHold],
+        targets: &[Option<InteractableType>],
+        handler: S,
+    ) -> &mut Self;
+}
+
+impl AddInteractionRule for App {
+    fn add_interaction_rule<P, S: IntoConditionalSystem<P>>(
+        &mut self,
+        action: Action,
+        holding: &[ViewModelHold],
+        targets: &[Option<InteractableType>],
+        handler: S,
+    ) -> &mut Self {
+        let mut rules = self
+            .world
+            .get_resource_or_insert_with(InteractionRules::default);
+        let id = rules.count;
+        rules.count += 1;
+        for hold in holding {
+            for target in targets {
+                let key = (action, *hold, *target);
+                if rules.handlers.insert(key, id).is_some() {
+                    panic!("two interaction rules for {key:?}");
+                }
+            }
+        }
+        self.add_system(
+            handler
+                .run_in_state(GameState::InOffice)
+                .run_if(move |pending: Res<PendingInteraction>| pending.handler == Some(id))
+                .after(InteractionSystemLabel::Resolve),
+        )
+    }
+}
+
+const HOLDING_ANY: &[ViewModelHold] = &[
+    ViewModelHold::Empty,
+    ViewModelHold::Hammer,
+    ViewModelHold::LoC,
+    ViewModelHold::LoCBundle,
+];
+pub const HOLDING_SOMETHING: &[ViewModelHold] = &[
+    ViewModelHold::Hammer,
+    ViewModelHold::LoC,
+    ViewModelHold::LoCBundle,
+];
+/// everything that can be picked up
+const PICKUPS: &[Option<InteractableType>] = &[
+    Some(InteractableType::Hammer),
+    Some(InteractableType::LineOfCode),
+    Some(InteractableType::LineOfCodeGlobule),
+];
+
 pub fn build(app: &mut App) -> &mut App {
+    use Action::*;
+    use InteractableType::*;
+    use ViewModelHold::*;
+
+    let grab_mode = app
+        .world
+        .get_resource::<PlayerConfig>()
+        .map(|c| c.grab_mode)
+        .unwrap_or_default();
+
     app.add_event::<MouseInteraction>();
-    app.insert_resource(InteractSingleSystemLock { ran: false });
     app.init_resource::<PlayerLookingAt>();
+    app.init_resource::<InteractionRules>();
+    app.init_resource::<PendingInteraction>();
     app.add_system(
         MouseInteraction::detect
             .run_in_state(GameState::InOffice)
-            .run_unless_resource_equals(PlayerStateMachine::INTERACTING),
-    );
-    app.add_system(
-        MouseInteraction::interact_mbleft_holdingloc_interactwithloctype
-            .run_in_state(GameState::InOffice)
-            .run_if_resource_equals(InteractSingleSystemLock { ran: false })
-            .label(InteractionSystemLabel::Loc)
-            .before(InteractionSystemLabel::LocBundle)
-            .before(InteractionSystemLabel::Hammer)
-            .before(InteractionSystemLabel::HoldingAny)
-            .before(InteractionSystemLabel::HoldingNone)
-            .before(InteractionSystemLabel::RightClick)
-            .before(InteractionSystemLabel::ClearRes),
+            .run_unless_resource_equals(PlayerStateMachine::INTERACTING)
+            .label(InteractionSystemLabel::Detect),
     );
     app.add_system(
-        MouseInteraction::interact_mbleft_holdinglocbundle_interactwithloctype
-            .run_in_state(GameState::InOffice)
-            .run_if_resource_equals(InteractSingleSystemLock { ran: false })
-            .label(InteractionSystemLabel::LocBundle)
-            .after(InteractionSystemLabel::Loc)
-            .before(InteractionSystemLabel::Hammer)
-            .before(InteractionSystemLabel::HoldingAny)
-            .before(InteractionSystemLabel::HoldingNone)
-            .before(InteractionSystemLabel::RightClick)
-            .before(InteractionSystemLabel::ClearRes),
-    );
-    // app.add_system(
-    //     MouseInteraction::interact_mbleft_holdinghammer_interactwithloc
-    //         .run_in_state(GameState::InOffice)
-    //         .label(InteractionSystemLabel::Hammer)
-    //         .after(InteractionSystemLabel::Loc)
-    //         .before(InteractionSystemLabel::Hammer)
-    //         .before(InteractionSystemLabel::HoldingAny)
-    //         .before(InteractionSystemLabel::HoldingNone),
-    // );
-    // app.add_system(
-    //     MouseInteraction::interact_mbleft_holdinghammer_interactwithlocbundle
-    //         .run_in_state(GameState::InOffice),
-    // );
-    app.add_system_set(
-        ConditionSet::new()
+        MouseInteraction::resolve
             .run_in_state(GameState::InOffice)
-            .run_if_resource_equals(InteractSingleSystemLock { ran: false })
-            .label(InteractionSystemLabel::Hammer)
-            .after(InteractionSystemLabel::Loc)
-            .after(InteractionSystemLabel::LocBundle)
-            .before(InteractionSystemLabel::HoldingAny)
-            .before(InteractionSystemLabel::HoldingNone)
-            .before(InteractionSystemLabel::ClearRes)
-            .before(InteractionSystemLabel::RightClick)
-            .with_system(MouseInteraction::interact_mbleft_holdinghammer_interactwithloc)
-            .with_system(MouseInteraction::interact_mbleft_holdinghammer_interactwithlocbundle)
-            .with_system(MouseInteraction::interact_mbleft_holdinghammer_interactnone)
-            .into(),
+            .label(InteractionSystemLabel::Resolve)
+            .after(InteractionSystemLabel::Detect),
     );
-    app.add_system(
-        MouseInteraction::interact_mbleft_holdingany_interactterminal
-            .run_in_state(GameState::InOffice)
-            .run_if_resource_equals(InteractSingleSystemLock { ran: false })
-            .label(InteractionSystemLabel::HoldingAny)
-            .after(InteractionSystemLabel::Loc)
-            .after(InteractionSystemLabel::LocBundle)
-            .after(InteractionSystemLabel::Hammer)
-            .before(InteractionSystemLabel::HoldingNone)
-            .before(InteractionSystemLabel::RightClick)
-            .before(InteractionSystemLabel::ClearRes),
-    );
-    app.add_system(
-        MouseInteraction::interact_mbleft_holdingnone_interactany
-            .run_in_state(GameState::InOffice)
-            .run_if_resource_equals(InteractSingleSystemLock { ran: false })
-            .label(InteractionSystemLabel::HoldingNone)
-            .after(InteractionSystemLabel::Loc)
-            .after(InteractionSystemLabel::LocBundle)
-            .after(InteractionSystemLabel::Hammer)
-            .after(InteractionSystemLabel::HoldingAny)
-            .before(InteractionSystemLabel::RightClick)
-            .before(InteractionSystemLabel::ClearRes),
-    );
-    app.add_system(
-        MouseInteraction::interact_mbleft_holdingnone_interactany
-            .run_in_state(GameState::InOffice)
-            .run_if_resource_equals(InteractSingleSystemLock { ran: false })
-            .label(InteractionSystemLabel::HoldingNone)
-            .after(InteractionSystemLabel::Loc)
-            .after(InteractionSystemLabel::LocBundle)
-            .after(InteractionSystemLabel::Hammer)
-            .after(InteractionSystemLabel::HoldingAny)
-            .before(InteractionSystemLabel::ClearRes),
-    );
-    app.add_system_set(
-        ConditionSet::new()
-            .run_in_state(GameState::InOffice)
-            .label(InteractionSystemLabel::RightClick)
-            .after(InteractionSystemLabel::Loc)
-            .after(InteractionSystemLabel::LocBundle)
-            .after(InteractionSystemLabel::Hammer)
-            .after(InteractionSystemLabel::HoldingAny)
-            .after(InteractionSystemLabel::HoldingNone)
-            .before(InteractionSystemLabel::ClearRes)
-            .with_system(MouseInteraction::interact_mbright_holdingany_interactnone)
-            .with_system(MouseInteraction::interact_mbright_holdingany_interactany)
-            .into(),
-    );
-    app.add_system(
-        MouseInteraction::clear_reader_because_fuck_you
-            .run_in_state(GameState::InOffice)
-            .label(InteractionSystemLabel::ClearRes)
-            .after(InteractionSystemLabel::Loc)
-            .after(InteractionSystemLabel::LocBundle)
-            .after(InteractionSystemLabel::Hammer)
-            .after(InteractionSystemLabel::HoldingAny)
-            .after(InteractionSystemLabel::HoldingNone)
-            .after(InteractionSystemLabel::RightClick),
+
+    app.add_interaction_rule(
+        Interact,
+        &[LoC],
+        &[Some(LineOfCode)],
+        MouseInteraction::attach_planks,
+    )
+    .add_interaction_rule(
+        Interact,
+        &[LoC],
+        &[Some(LineOfCodeGlobule)],
+        MouseInteraction::insert_into_bundle,
+    )
+    .add_interaction_rule(
+        Interact,
+        &[LoCBundle],
+        &[Some(LineOfCode), Some(LineOfCodeGlobule)],
+        MouseInteraction::merge_bundles,
+    )
+    .add_interaction_rule(
+        OpenTerminal,
+        HOLDING_ANY,
+        &[Some(Terminal)],
+        MouseInteraction::use_terminal,
+    )
+    .add_interaction_rule(Swap, HOLDING_SOMETHING, PICKUPS, MouseInteraction::swap)
+    .add_interaction_rule(
+        Swap,
+        &[Empty],
+        &[Some(LineOfCodeGlobule)],
+        MouseInteraction::pull_from_bundle,
     );
 
-    app
+    match grab_mode {
+        GrabMode::ViewModel => {
+            app.add_interaction_rule(Interact, &[Empty], PICKUPS, MouseInteraction::pick_up)
+        }
+        // the hammer still goes in hand, it can't be swung from a spring
+        GrabMode::Physics => app
+            .add_interaction_rule(
+                Interact,
+                &[Empty],
+                &[Some(InteractableType::Hammer)],
+                MouseInteraction::pick_up,
+            )
+            .add_interaction_rule(
+                Interact,
+                &[Empty],
+                &[Some(LineOfCode), Some(LineOfCodeGlobule)],
+                PhysicsGrab::grab,
+            ),
+    }
 }
 
 #[derive(Component, Clone, Debug)]
 pub struct OrderedChildren(pub Vec<Entity>);
 
+/// distance between the centres of two planks next to each other in a bundle
+const PLANK_SPACING: f32 = 0.1;
+
+impl OrderedChildren {
+    /// where the `i`th plank of a bundle of `len` planks sits, relative to the bundle
+    /// the planks are centred on the bundle's origin
+    pub fn plank_transform(i: usize, len: usize) -> Transform {
+        let centre = (len as f32 - 1.0) / 2.0;
+        Transform::from_xyz(PLANK_SPACING * (centre - i as f32), 0.0, 0.0)
+    }
+
+    /// The plank closest to `point` on a bundle, as a fractional index
+    /// e.g. 1.5 is halfway between the second and third plank
+    pub fn slot_at(&self, bundle_trans: &GlobalTransform, point: Vec3) -> f32 {
+        let local = bundle_trans
+            .compute_matrix()
+            .inverse()
+            .transform_point3(point);
+        (self.0.len() as f32 - 1.0) / 2.0 - local.x / PLANK_SPACING
+    }
+
+    /// Each plank's own shape, placed where that plank sits in the bundle
+    pub fn collider(&self, colliders: &Query<&Collider>) -> Collider {
+        let shapes = self
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, plank)| {
+                let trans = Self::plank_transform(i, self.0.len());
+                let shape = colliders
+                    .get(*plank)
+                    .cloned()
+                    .unwrap_or_else(|_| plank_collider());
+                (trans.translation, trans.rotation, shape)
+            })
+            .collect();
+        Collider::compound(shapes)
+    }
+
+    /// Moves every plank into its place and fits the bundle's collider around them
+    pub fn relayout(&self, commands: &mut Commands, bundle: Entity, colliders: &Query<&Collider>) {
+        for (i, plank) in self.0.iter().enumerate() {
+            commands
+                .entity(*plank)
+                .insert(Self::plank_transform(i, self.0.len()));
+        }
+        commands.entity(bundle).insert(self.collider(colliders));
+    }
+}
+
 impl MouseInteraction {
     pub fn detect(
-        mut lock: ResMut<InteractSingleSystemLock>,
         player_config: Res<PlayerConfig>,
         mut interacts: EventWriter<MouseInteraction>,
-        bttns: Res<Input<MouseButton>>,
+        actions: Res<Actions>,
         rapier: Res<RapierContext>,
         camera_query: Query<&Transform, With<PlayerCamera>>,
         mut looking_at: ResMut<PlayerLookingAt>,
     ) {
         let camera_trans = camera_query.single();
-        let mut pressed = bttns.get_just_pressed();
-
-        // lmb has been pressed
         let ray_origin = camera_trans.translation;
         let ray_dir = camera_trans.rotation * -Vec3::Z;
         let max_toi = player_config.reach_dist;
@@ -212,13 +298,12 @@ impl MouseInteraction {
                     entity: Some(entity),
                     dist: toi,
                 };
-                if let Some(button) = pressed.next() {
-                    lock.ran = false;
+                for action in actions.get_just_pressed() {
                     interacts.send(MouseInteraction {
-                        button: *button,
+                        action: *action,
                         with: entity,
-                        direction: ray_dir,
                         toi,
+                        point: ray_origin + ray_dir * toi,
                     });
                 }
             }
@@ -227,18 +312,55 @@ impl MouseInteraction {
         }
     }
 
-    pub fn interact_mbleft_holdingloc_interactwithloctype(
+    /// Matches this frame's click against the `InteractionRules`
+    pub fn resolve(
+        mut pending: ResMut<PendingInteraction>,
+        mut clicks: EventReader<MouseInteraction>,
+        rules: Res<InteractionRules>,
+        actions: Res<Actions>,
+        player_state: Res<PlayerStateMachine>,
+        viewmodel_query: Query<&ViewModel>,
+        interact_type: Query<&Interactable>,
+    ) {
+        *pending = PendingInteraction::default();
+        let clicks = clicks.iter().cloned().collect::<Vec<_>>();
+        if *player_state == PlayerStateMachine::INTERACTING {
+            return;
+        }
+        let holding = match viewmodel_query.get_single() {
+            Ok(v) => v.holding(),
+            Err(_) => return,
+        };
+        for action in actions.get_just_pressed() {
+            let event = clicks.iter().find(|ev| ev.action == *action);
+            let target = event
+                .and_then(|ev| interact_type.get(ev.with).ok())
+                .map(|i| i.itype());
+            if let Some(handler) = rules.handlers.get(&(*action, holding, target)) {
+                *pending = PendingInteraction {
+                    handler: Some(*handler),
+                    event: event.cloned(),
+                };
+                return;
+            }
+        }
+    }
+
+    /// Two loose planks make a bundle
+    pub fn attach_planks(
         mut commands: Commands,
-        mut reader: EventReader<MouseInteraction>,
+        pending: Res<PendingInteraction>,
         mut interact_sfx_event: EventWriter<InteractSoundEvent>,
-        mut lock: ResMut<InteractSingleSystemLock>,
         mut player_state: ResMut<PlayerStateMachine>,
         children: Query<&Children>,
-        mut ordered_children: Query<&mut OrderedChildren>,
-        mut transform: Query<&mut Transform>,
+        transform: Query<&Transform>,
+        colliders: Query<&Collider>,
         mut viewmodel_query: Query<(&mut ViewModel, Entity), With<ViewModel>>,
-        interact_type: Query<&Interactable>,
     ) {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
         let (mut viewmodel, vm_ent) = viewmodel_query.single_mut();
         let vm_child_id = match children.get(vm_ent) {
             Ok(v) => match v.get(0) {
@@ -247,652 +369,427 @@ impl MouseInteraction {
             },
             Err(_) => return,
         };
-        for event in reader.iter() {
-            let interacting_ent = event.with;
-            let interact_typ = match interact_type.get(interacting_ent) {
-                Ok(inter) => *inter,
-                Err(_) => {
-                    continue;
-                }
-            };
-            if event.button == MouseButton::Left
-                && viewmodel.holding() == ViewModelHold::LoC
-                && interact_typ == Interactable::LOC
-            {
-                let current_gnd_trans = transform.get_mut(interacting_ent).unwrap();
-                let parent_new_trans = *current_gnd_trans;
-                let new = commands
-                    .spawn()
-                    .insert_bundle(TransformBundle::from_transform(parent_new_trans))
-                    .insert(Interactable::LOCG)
-                    .insert_bundle(PhysicsBundle {
-                        body: RigidBody::Dynamic,
-                        collider: ColliderBundle {
-                            collider: Collider::cuboid(0.1, 0.015, 0.75),
-                            groups: ActiveCollisionTypes::all(),
-                            ..Default::default()
-                        },
-                        c_groups: interactable_dynamic_body(),
-                        ..Default::default()
-                    })
-                    .insert(OrderedChildren(vec![interacting_ent, vm_child_id]))
-                    .insert(LoCEntity)
-                    .id();
-                commands
-                    .entity(vm_child_id)
-                    .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
-                        -0.05, 0.0, 0.0,
-                    )))
-                    .insert(RigidBody::Fixed)
-                    .insert(none())
-                    .insert(ActiveCollisionTypes::empty())
-                    .remove::<Interactable>();
-                commands
-                    .entity(interacting_ent)
-                    .insert_bundle(TransformBundle::from_transform(Transform::from_xyz(
-                        0.05, 0.0, 0.0,
-                    )))
-                    .insert(RigidBody::Fixed)
-                    .insert(none())
-                    .insert(ActiveCollisionTypes::empty())
-                    .remove::<Interactable>();
-                commands
-                    .entity(vm_ent)
-                    .remove_children(children.get(vm_ent).unwrap());
-                commands
-                    .entity(new)
-                    .push_children(&[interacting_ent, vm_child_id]);
-                viewmodel.change_holding(ViewModelHold::Empty);
-                player_state.change_state(PlayerState::Idle);
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::Attach,
-                });
-                lock.i_ran_dawddy();
-                return;
-            } else if event.button == MouseButton::Left
-                && viewmodel.holding() == ViewModelHold::LoC
-                && interact_typ == Interactable::LOCG
-            {
-                let mut existing_children = ordered_children.get_mut(interacting_ent).unwrap();
-
-                commands
-                    .entity(vm_ent)
-                    .remove_children(children.get(vm_ent).unwrap());
-                commands
-                    .entity(interacting_ent)
-                    .remove_children(&existing_children.0);
-                let children_offset = existing_children.0.len() as i32 / 2;
-                existing_children.0.push(vm_child_id);
-                dbg!(&*existing_children);
-                for (i, &child) in existing_children.0.iter().enumerate() {
-                    let new_item_trans = Transform::from_xyz(
-                        0.1 * (i as i32 - children_offset) as f32 * -1.0,
-                        0.0,
-                        0.0,
-                    );
-                    // commands.entity(child).log_components();
-                    commands
-                        .entity(child)
-                        .insert(new_item_trans)
-                        .insert(RigidBody::Fixed)
-                        .insert(none())
-                        .insert(ActiveCollisionTypes::empty());
-
-                    commands.entity(interacting_ent).add_child(child);
-                }
-                viewmodel.change_holding(ViewModelHold::Empty);
-
-                let hx = {
-                    let ch_len = existing_children.0.len() + 1;
-                    if existing_children.0.len() % 2 == 0 {
-                        0.05 * ch_len as f32
-                    } else {
-                        0.05 * (ch_len - 1) as f32 + 0.025
-                    }
-                };
-
-                commands
-                    .entity(interacting_ent)
-                    .insert(Collider::cuboid(hx, 0.015, 0.75))
-                    .insert(LoCEntity);
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::Attach,
-                });
-                player_state.change_state(PlayerState::Idle);
-                lock.i_ran_dawddy();
-                return;
-            }
-        }
+        let interacting_ent = event.with;
+        let parent_new_trans = *transform.get(interacting_ent).unwrap();
+        let planks = OrderedChildren(vec![interacting_ent, vm_child_id]);
+        let new = commands
+            .spawn()
+            .insert_bundle(TransformBundle::from_transform(parent_new_trans))
+            .insert(Interactable::LOCG)
+            .insert_bundle(PhysicsBundle {
+                body: RigidBody::Dynamic,
+                collider: ColliderBundle {
+                    collider: planks.collider(&colliders),
+                    groups: ActiveCollisionTypes::all(),
+                    ..Default::default()
+                },
+                c_groups: interactable_dynamic_body(),
+                ..Default::default()
+            })
+            .insert(planks)
+            .insert(LoCEntity)
+            .id();
+        commands
+            .entity(vm_child_id)
+            .insert_bundle(TransformBundle::from_transform(
+                OrderedChildren::plank_transform(1, 2),
+            ))
+            .insert(RigidBody::Fixed)
+            .insert(none())
+            .insert(ActiveCollisionTypes::empty())
+            .remove::<Interactable>();
+        commands
+            .entity(interacting_ent)
+            .insert_bundle(TransformBundle::from_transform(
+                OrderedChildren::plank_transform(0, 2),
+            ))
+            .insert(RigidBody::Fixed)
+            .insert(none())
+            .insert(ActiveCollisionTypes::empty())
+            .remove::<Interactable>();
+        commands
+            .entity(vm_ent)
+            .remove_children(children.get(vm_ent).unwrap());
+        commands
+            .entity(new)
+            .push_children(&[interacting_ent, vm_child_id]);
+        viewmodel.change_holding(ViewModelHold::Empty);
+        player_state.change_state(PlayerState::Idle);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Attach,
+        });
     }
 
-    pub fn interact_mbleft_holdinglocbundle_interactwithloctype(
-        mut lock: ResMut<InteractSingleSystemLock>,
+    /// The held plank goes into the gap of the bundle closest to where we're looking
+    pub fn insert_into_bundle(
+        mut commands: Commands,
+        pending: Res<PendingInteraction>,
         mut interact_sfx_event: EventWriter<InteractSoundEvent>,
-        mut reader: EventReader<MouseInteraction>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        children: Query<&Children>,
+        mut ordered_children: Query<&mut OrderedChildren>,
+        global_transforms: Query<&GlobalTransform>,
+        colliders: Query<&Collider>,
+        mut viewmodel_query: Query<(&mut ViewModel, Entity), With<ViewModel>>,
+    ) {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
+        let (mut viewmodel, vm_ent) = viewmodel_query.single_mut();
+        let vm_child_id = match children.get(vm_ent) {
+            Ok(v) => match v.get(0) {
+                Some(v) => *v,
+                None => return,
+            },
+            Err(_) => return,
+        };
+        let interacting_ent = event.with;
+        let mut existing_children = ordered_children.get_mut(interacting_ent).unwrap();
+        let bundle_trans = global_transforms.get(interacting_ent).unwrap();
+        let at = (existing_children.slot_at(bundle_trans, event.point) + 0.5)
+            .round()
+            .clamp(0.0, existing_children.0.len() as f32) as usize;
+
+        commands
+            .entity(vm_ent)
+            .remove_children(children.get(vm_ent).unwrap());
+        existing_children.0.insert(at, vm_child_id);
+        commands
+            .entity(vm_child_id)
+            .insert(RigidBody::Fixed)
+            .insert(none())
+            .insert(ActiveCollisionTypes::empty());
+        commands.entity(interacting_ent).add_child(vm_child_id);
+        existing_children.relayout(&mut commands, interacting_ent, &colliders);
+        viewmodel.change_holding(ViewModelHold::Empty);
+
+        commands.entity(interacting_ent).insert(LoCEntity);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Attach,
+        });
+        player_state.change_state(PlayerState::Idle);
+    }
+
+    /// The held bundle and the plank or bundle clicked on become one bundle,
+    /// with the clicked planks on top
+    pub fn merge_bundles(
         mut commands: Commands,
+        pending: Res<PendingInteraction>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
         mut player_state: ResMut<PlayerStateMachine>,
         transform: Query<&Transform>,
         mut viewmodel_query: Query<(&mut ViewModel, Entity, &Children), With<ViewModel>>,
         interact_type: Query<&Interactable, Without<ViewModel>>,
         children: Query<&Children>,
+        colliders: Query<&Collider>,
         mut ordered_children: Query<&mut OrderedChildren>,
     ) {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
         let (mut viewmodel, vm_ent, vm_children) = viewmodel_query.single_mut();
         let vm_child_id: Entity = match vm_children.get(0) {
             Some(v) => *v,
             None => return,
         };
-
-        for event in reader.iter() {
-            let interacting_ent = event.with;
-            let interact_typ = match interact_type.get(interacting_ent) {
-                Ok(inter) => *inter,
-                Err(_) => continue,
-            };
-
-            if event.button == MouseButton::Left && viewmodel.holding() == ViewModelHold::LoCBundle
-            {
-                let new_locg_position = *transform.get(interacting_ent).unwrap();
-                let mut interact_to_add = match interact_typ.itype() {
-                    InteractableType::LineOfCode => {
-                        vec![interacting_ent]
-                    }
-                    InteractableType::LineOfCodeGlobule => {
-                        // event,with is an LOCG
-                        let a = &mut ordered_children.get_mut(interacting_ent).unwrap().0;
-
-                        commands.entity(interacting_ent).remove_children(&a);
-                        commands.entity(interacting_ent).despawn(); // despawn the LOCG itself
-                        let ret = a.clone();
-                        a.clear();
-                        ret
-                    }
-                    _ => return,
-                };
-                // now append our thing
-                let mut new_locg_things = &mut ordered_children.get_mut(vm_child_id).unwrap().0;
-                interact_to_add.append(&mut new_locg_things);
-                let new_locg_things = interact_to_add;
-                // now for some cleanup
-                commands
-                    .entity(vm_ent)
-                    .remove_children(children.get(vm_ent).unwrap());
-                commands
-                    .entity(vm_child_id)
-                    .remove_children(&new_locg_things);
-                // create the new locg
-
-                let new_locg = commands
-                    .spawn()
-                    .insert_bundle(TransformBundle::from_transform(new_locg_position))
-                    .insert(Interactable::LOCG)
-                    .insert_bundle(PhysicsBundle {
-                        body: RigidBody::Dynamic,
-                        collider: ColliderBundle {
-                            collider: Collider::cuboid(0.1, 0.015, 0.75),
-                            groups: ActiveCollisionTypes::all(),
-                            ..Default::default()
-                        },
-                        c_groups: interactable_dynamic_body(),
-                        ..Default::default()
-                    })
-                    .insert(OrderedChildren(new_locg_things.clone()))
-                    .insert(LoCEntity)
-                    .id();
-                // insert the childrernn
-                let children_offset = new_locg_things.len() as i32 / 2;
-                for (i, child) in new_locg_things.iter().enumerate() {
-                    let new_item_trans = Transform::from_xyz(
-                        0.1 * (i as i32 - children_offset) as f32 * -1.0,
-                        0.0,
-                        0.0,
-                    );
-                    commands
-                        .entity(*child)
-                        .insert(new_item_trans)
-                        .insert(RigidBody::Fixed)
-                        .insert(none())
-                        .insert(ActiveCollisionTypes::empty())
-                        .insert(Collider::cuboid(0.05, 0.015, 0.75));
-                    commands.entity(new_locg).push_children(&[*child]);
-                }
-                player_state.change_state(PlayerState::Idle);
-                viewmodel.change_holding(ViewModelHold::Empty);
-
-                let hx = {
-                    let ch_len = new_locg_things.len();
-                    if new_locg_things.len() % 2 == 0 {
-                        0.05 * ch_len as f32
-                    } else {
-                        0.05 * (ch_len - 1) as f32 + 0.05
-                    }
-                };
-
-                commands
-                    .entity(new_locg)
-                    .insert(Collider::cuboid(hx, 0.015, 0.75));
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::Attach,
-                });
-                lock.i_ran_dawddy();
-                return;
-            }
-        }
-    }
-
-    pub fn interact_mbleft_holdinghammer_interactwithloc(
-        mut lock: ResMut<InteractSingleSystemLock>,
-        mut reader: EventReader<MouseInteraction>,
-        mut sound_event: EventWriter<HammerSoundEvent>,
-        mut commands: Commands,
-        mut viewmodel_query: Query<&ViewModel>,
-        interact_type: Query<&Interactable, Without<ViewModel>>,
-    ) {
-        let viewmodel = viewmodel_query.single_mut();
-
-        for event in reader.iter() {
-            let interact_typ = match interact_type.get(event.with) {
-                Ok(inter) => *inter,
-                Err(_) => continue,
-            };
-            if event.button == MouseButton::Left
-                && viewmodel.holding() == ViewModelHold::Hammer
-                && interact_typ == Interactable::LOC
-            {
-                let ray_dir = event.direction;
-                let ray_dir_y_inv = Vec3::new(ray_dir.x, -ray_dir.y, ray_dir.z);
-                commands.entity(event.with).insert(ExternalImpulse {
-                    impulse: ray_dir_y_inv * 0.05,
-                    ..Default::default()
-                });
-                sound_event.send(HammerSoundEvent { hit: true });
-                lock.i_ran_dawddy();
-            }
-        }
-    }
-
-    pub fn interact_mbleft_holdinghammer_interactwithlocbundle(
-        mut lock: ResMut<InteractSingleSystemLock>,
-        mut reader: EventReader<MouseInteraction>,
-        mut sound_event: EventWriter<HammerSoundEvent>,
-        mut commands: Commands,
-        mut viewmodel_query: Query<&mut ViewModel, With<ViewModel>>,
-        interact_type: Query<&Interactable, Without<ViewModel>>,
-        transgender: Query<&Transform>,
-        children: Query<&Children>,
-    ) {
-        let viewmodel = viewmodel_query.single_mut();
-
-        for event in reader.iter() {
-            // if event.toi > 1.5 {
-            //     continue;
-            // }
-            let interact_typ = match interact_type.get(event.with) {
-                Ok(inter) => *inter,
-                Err(_) => continue,
-            };
-            if event.button == MouseButton::Left
-                && viewmodel.holding() == ViewModelHold::Hammer
-                && interact_typ == Interactable::LOCG
-            {
-                let floor_item_children = match children.get(event.with) {
-                    Ok(c) => c,
-                    Err(_) => return,
-                };
-
-                let current_bundle_trans = *transgender.get(event.with).unwrap();
-                let mut random = SmallRng::from_entropy();
-                for (i, e) in floor_item_children.iter().enumerate() {
-                    commands.entity(event.with).remove_children(&[*e]);
-                    let random_x = random.gen_range(-1.0..=1.0);
-                    let random_z = random.gen_range(-1.0..=1.0);
-                    let force = Vec3::new(random_x, 1.0, random_z);
-                    let mut new_trans = current_bundle_trans;
-                    new_trans.translation.y += 0.02 * i as f32;
-                    commands
-                        .entity(*e)
-                        .insert_bundle(TransformBundle::from_transform(new_trans))
-                        .insert(ExternalImpulse {
-                            impulse: force * 0.015,
-                            ..Default::default()
-                        })
-                        .insert(Interactable::LOC)
-                        .insert(RigidBody::Dynamic)
-                        .insert(ActiveCollisionTypes::all())
-                        .insert(interactable_dynamic_body());
-                }
-                commands.entity(event.with).despawn();
-                sound_event.send(HammerSoundEvent { hit: true });
-                lock.i_ran_dawddy();
-            }
-        }
-    }
-
-    pub fn interact_mbleft_holdinghammer_interactnone(
-        mut events: EventWriter<HammerSoundEvent>,
-        bttns: Res<Input<MouseButton>>,
-        looking_at: Res<PlayerLookingAt>,
-        viewmodel_query: Query<&ViewModel, With<ViewModel>>,
-        interactable_q: Query<&Interactable>,
-    ) {
-        let viewmodel = match viewmodel_query.get_single() {
-            Ok(v) => v,
+        let interacting_ent = event.with;
+        let interact_typ = match interact_type.get(interacting_ent) {
+            Ok(inter) => *inter,
             Err(_) => return,
         };
 
-        for event in bttns.get_just_pressed() {
-            let interacttyp = looking_at.entity.and_then(|e| interactable_q.get(e).ok());
-
-            if *event == MouseButton::Left
-                && interacttyp.is_none()
-                && viewmodel.holding() == ViewModelHold::Hammer
-            {
-                events.send(HammerSoundEvent { hit: false });
+        let new_locg_position = *transform.get(interacting_ent).unwrap();
+        let mut interact_to_add = match interact_typ.itype() {
+            InteractableType::LineOfCode => {
+                vec![interacting_ent]
+            }
+            InteractableType::LineOfCodeGlobule => {
+                // event,with is an LOCG
+                let a = &mut ordered_children.get_mut(interacting_ent).unwrap().0;
+
+                commands.entity(interacting_ent).remove_children(a);
+                commands.entity(interacting_ent).despawn(); // despawn the LOCG itself
+                let ret = a.clone();
+                a.clear();
+                ret
             }
+            _ => return,
+        };
+        // now append our thing
+        let new_locg_things = &mut ordered_children.get_mut(vm_child_id).unwrap().0;
+        interact_to_add.append(new_locg_things);
+        let new_locg_things = interact_to_add;
+        // now for some cleanup
+        commands
+            .entity(vm_ent)
+            .remove_children(children.get(vm_ent).unwrap());
+        commands
+            .entity(vm_child_id)
+            .remove_children(&new_locg_things);
+        // create the new locg
+        let planks = OrderedChildren(new_locg_things.clone());
+        let new_locg = commands
+            .spawn()
+            .insert_bundle(TransformBundle::from_transform(new_locg_position))
+            .insert(Interactable::LOCG)
+            .insert_bundle(PhysicsBundle {
+                body: RigidBody::Dynamic,
+                collider: ColliderBundle {
+                    collider: planks.collider(&colliders),
+                    groups: ActiveCollisionTypes::all(),
+                    ..Default::default()
+                },
+                c_groups: interactable_dynamic_body(),
+                ..Default::default()
+            })
+            .insert(planks)
+            .insert(LoCEntity)
+            .id();
+        // insert the childrernn
+        for (i, child) in new_locg_things.iter().enumerate() {
+            let new_item_trans = OrderedChildren::plank_transform(i, new_locg_things.len());
+            commands
+                .entity(*child)
+                .insert(new_item_trans)
+                .insert(RigidBody::Fixed)
+                .insert(none())
+                .insert(ActiveCollisionTypes::empty());
+            commands.entity(new_locg).push_children(&[*child]);
         }
+        player_state.change_state(PlayerState::Idle);
+        viewmodel.change_holding(ViewModelHold::Empty);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Attach,
+        });
     }
 
-    pub fn interact_mbleft_holdingany_interactterminal(
-        mut lock: ResMut<InteractSingleSystemLock>,
+    pub fn use_terminal(
+        pending: Res<PendingInteraction>,
         mut interact_sfx_event: EventWriter<InteractSoundEvent>,
-        mut reader: EventReader<MouseInteraction>,
         mut state: ResMut<PlayerStateMachine>,
-        interact_type: Query<&Interactable, Without<ViewModel>>,
+        mut active_terminal: ResMut<ActiveTerminal>,
+        names: Query<&EName>,
     ) {
-        for event in reader.iter() {
-            let interact_typ = match interact_type.get(event.with) {
-                Ok(inter) => *inter,
-                Err(_) => continue,
-            };
-            if event.button == MouseButton::Left && interact_typ == Interactable::TERMINAL {
-                state.change_state(PlayerState::Interacting);
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::TerminalEnter,
-                });
-                lock.i_ran_dawddy();
-            }
-        }
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
+        active_terminal.name = names.get(event.with).ok().map(|n| n.id.clone());
+        state.change_state(PlayerState::Interacting);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::TerminalEnter,
+        });
     }
 
-    pub fn interact_mbleft_holdingnone_interactany(
-        mut lock: ResMut<InteractSingleSystemLock>,
-        mut reader: EventReader<MouseInteraction>,
-        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+    pub fn pick_up(
         mut commands: Commands,
+        pending: Res<PendingInteraction>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
         mut viewmodel_query: Query<(&mut ViewModel, Entity), With<ViewModel>>,
         mut player_state: ResMut<PlayerStateMachine>,
         interact_type: Query<&Interactable, Without<ViewModel>>,
     ) {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
         let (mut viewmodel, vm_ent) = match viewmodel_query.get_single_mut() {
             Ok(v) => v,
             Err(_) => return,
         };
-        // remove the first entity if it has nothing but parent
-
-        for event in reader.iter() {
-            let interacting_ent = event.with;
-            let interact_typ = match interact_type.get(interacting_ent) {
-                Ok(inter) => *inter,
-                Err(_) => return,
-            };
-            if event.button == MouseButton::Left && viewmodel.holding() == ViewModelHold::Empty {
-                let new_view_type = match interact_typ.itype() {
-                    InteractableType::Hammer => ViewModelHold::Hammer,
-                    InteractableType::LineOfCode => ViewModelHold::LoC,
-                    InteractableType::LineOfCodeGlobule => ViewModelHold::LoCBundle,
-                    InteractableType::Terminal => return,
-                };
+        let interacting_ent = event.with;
+        let interact_typ = match interact_type.get(interacting_ent) {
+            Ok(inter) => *inter,
+            Err(_) => return,
+        };
+        let new_view_type = match interact_typ.itype() {
+            InteractableType::Hammer => ViewModelHold::Hammer,
+            InteractableType::LineOfCode => ViewModelHold::LoC,
+            InteractableType::LineOfCodeGlobule => ViewModelHold::LoCBundle,
+            InteractableType::Terminal => return,
+        };
 
-                let transgender = match new_view_type {
-                    ViewModelHold::Empty => return,
-                    ViewModelHold::Hammer => {
-                        let mut new_trans = Transform::from_xyz(-0.2, 0.3, 0.0);
-                        new_trans.rotate_local_y(-1.57);
-                        new_trans.rotate_local_x(0.3491);
-                        new_trans.rotate_local_z(-0.1745329);
-                        new_trans
-                    }
-                    ViewModelHold::LoC => {
-                        let mut new_trans = Transform::from_xyz(-0.2, 0.5, 0.0);
-                        new_trans.rotate_local_y(1.57);
-                        new_trans.rotate_local_x(-0.1745329);
-                        new_trans.rotate_local_z(0.3491);
-                        new_trans
-                    }
-                    ViewModelHold::LoCBundle => {
-                        let mut new_trans = Transform::from_xyz(-0.2, 0.3, 0.0);
-                        new_trans.rotate_local_y(-1.57);
-                        new_trans.rotate_local_x(2.967);
-                        new_trans.rotate_local_z(-1.);
-                        new_trans
-                    }
-                };
-                viewmodel.change_holding(new_view_type);
-                commands
-                    .entity(interacting_ent)
-                    .remove::<Interactable>()
-                    .remove::<RigidBody>()
-                    .remove::<ActiveCollisionTypes>()
-                    .remove::<CollisionGroups>()
-                    .remove::<Transform>()
-                    .insert(transgender)
-                    .insert(ActiveCollisionTypes::empty())
-                    .insert(none())
-                    .insert(RigidBody::Fixed);
-                commands.entity(vm_ent).despawn_descendants();
-                commands.entity(vm_ent).push_children(&[interacting_ent]);
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::Pickup,
-                });
-                player_state.change_state(PlayerState::Holding);
-                lock.i_ran_dawddy();
-                return;
-            }
-        }
+        viewmodel.change_holding(new_view_type);
+        commands
+            .entity(interacting_ent)
+            .remove::<Interactable>()
+            .remove::<RigidBody>()
+            .remove::<ActiveCollisionTypes>()
+            .remove::<CollisionGroups>()
+            .remove::<Transform>()
+            .insert(held_transform(new_view_type))
+            .insert(ActiveCollisionTypes::empty())
+            .insert(none())
+            .insert(RigidBody::Fixed);
+        commands.entity(vm_ent).despawn_descendants();
+        commands.entity(vm_ent).push_children(&[interacting_ent]);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Pickup,
+        });
+        player_state.change_state(PlayerState::Holding);
     }
 
-    pub fn interact_mbright_holdingany_interactnone(
+    pub fn swap(
         mut commands: Commands,
+        pending: Res<PendingInteraction>,
         mut interact_sfx_event: EventWriter<InteractSoundEvent>,
-        looking_at: Res<PlayerLookingAt>,
-        mut player_state: ResMut<PlayerStateMachine>,
-        bttns: Res<Input<MouseButton>>,
         mut viewmodel_query: Query<(&mut ViewModel, Entity, &Children), With<ViewModel>>,
-        camera_query: Query<&Transform, With<PlayerCamera>>,
-        player_query: Query<&Velocity, With<Player>>,
-        interactable_q: Query<&Interactable>,
-        children: Query<&Children>,
+        interact_type: Query<&Interactable, Without<ViewModel>>,
+        transforms: Query<&Transform>,
     ) {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
         let (mut viewmodel, vm_ent, vm_children) = match viewmodel_query.get_single_mut() {
             Ok(v) => v,
             Err(_) => return,
         };
-        let camera_trans = camera_query.single();
-        let player_vel = player_query.get_single().copied().unwrap_or_default();
-        // remove the first entity if it has nothing but parent
-
-        for event in bttns.get_just_pressed() {
-            let interacttyp = looking_at.entity.and_then(|e| interactable_q.get(e).ok());
-            if *event == MouseButton::Right && interacttyp.is_none() {
-                let force_dir = camera_trans.rotation * -Vec3::Z;
-
-                // new tranform
-                let vm_trans = Vec3::new(0.0, 0.0, -1.0);
-                let c_rot = camera_trans.rotation;
-                let fin = ((c_rot * vm_trans).normalize_or_zero() * 2.0) + camera_trans.translation;
-
-                let child: Entity = match vm_children.get(0) {
-                    Some(v) => *v,
-                    None => return,
-                };
-
-                let interact_type = match viewmodel.holding() {
-                    ViewModelHold::Empty => return,
-                    ViewModelHold::Hammer => Interactable::HAMMER,
-                    ViewModelHold::LoCBundle => Interactable::LOCG,
-                    ViewModelHold::LoC => Interactable::LOC,
-                };
+        let interacting_ent = event.with;
+        let interact_typ = match interact_type.get(interacting_ent) {
+            Ok(inter) => *inter,
+            Err(_) => return,
+        };
+        let ground_item_position = transforms.get(interacting_ent).unwrap();
+        let viewmodel_held: Entity = *vm_children.get(0).unwrap();
+
+        let new_view_type = match interact_typ.itype() {
+            InteractableType::Hammer => ViewModelHold::Hammer,
+            InteractableType::LineOfCode => ViewModelHold::LoC,
+            InteractableType::LineOfCodeGlobule => ViewModelHold::LoCBundle,
+            InteractableType::Terminal => return,
+        };
 
-                let force_extra_multi = match interact_type.itype() {
-                    InteractableType::Hammer => 4.0,
-                    InteractableType::LineOfCode => 1.0,
-                    InteractableType::LineOfCodeGlobule => match children.get(child) {
-                        Ok(c) => c.len() as f32,
-                        Err(_) => 1.0,
-                    },
-                    InteractableType::Terminal => return,
-                };
+        let interact_type = match held_interactable(viewmodel.holding()) {
+            Some(i) => i,
+            None => return,
+        };
 
-                commands.entity(vm_ent).remove_children(&[child]);
-                commands
-                    .entity(child)
-                    .insert_bundle(TransformBundle::from_transform(
-                        Transform::from_translation(fin),
-                    ))
-                    .insert(RigidBody::Dynamic)
-                    .insert(ActiveCollisionTypes::all())
-                    .insert(interactable_dynamic_body())
-                    .insert(ExternalImpulse {
-                        impulse: force_dir * 0.05 * force_extra_multi,
-                        ..Default::default()
-                    })
-                    .insert(interact_type)
-                    .insert(player_vel);
-
-                viewmodel.change_holding(ViewModelHold::Empty);
-                player_state.change_state(PlayerState::Idle);
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::Throw,
-                });
-            }
-        }
+        viewmodel.change_holding(new_view_type);
+
+        commands.entity(vm_ent).remove_children(&[viewmodel_held]);
+        commands
+            .entity(viewmodel_held)
+            .insert_bundle(TransformBundle::from_transform(*ground_item_position))
+            .insert(RigidBody::Dynamic)
+            .insert(ActiveCollisionTypes::all())
+            .insert(interactable_dynamic_body())
+            .insert(interact_type);
+        let new_hold = commands
+            .entity(interacting_ent)
+            .remove::<Interactable>()
+            .remove::<RigidBody>()
+            .remove::<ActiveCollisionTypes>()
+            .remove::<CollisionGroups>()
+            .remove::<Transform>()
+            .insert(held_transform(new_view_type))
+            .insert(ActiveCollisionTypes::empty())
+            .insert(none())
+            .insert(RigidBody::Fixed)
+            .id();
+        commands.entity(vm_ent).push_children(&[new_hold]);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Pickup,
+        });
     }
 
-    pub fn interact_mbright_holdingany_interactany(
-        mut lock: ResMut<InteractSingleSystemLock>,
-        mut reader: EventReader<MouseInteraction>,
-        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+    /// Pulls the plank being looked at out of a bundle and into the player's hand
+    pub fn pull_from_bundle(
         mut commands: Commands,
-        mut viewmodel_query: Query<(&mut ViewModel, Entity, &Children), With<ViewModel>>,
-        interact_type: Query<&Interactable, Without<ViewModel>>,
+        pending: Res<PendingInteraction>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        mut viewmodel_query: Query<(&mut ViewModel, Entity), With<ViewModel>>,
         transforms: Query<&Transform>,
+        global_transforms: Query<&GlobalTransform>,
+        colliders: Query<&Collider>,
+        mut ordered_children: Query<&mut OrderedChildren>,
     ) {
-        let (mut viewmodel, vm_ent, vm_children) = match viewmodel_query.get_single_mut() {
+        let event = match pending.event() {
+            Some(e) => e,
+            None => return,
+        };
+        let (mut viewmodel, vm_ent) = match viewmodel_query.get_single_mut() {
             Ok(v) => v,
             Err(_) => return,
         };
-        // remove the first entity if it has nothing but parent
-
-        for event in reader.iter() {
-            let interacting_ent = event.with;
-            let interact_typ = match interact_type.get(interacting_ent) {
-                Ok(inter) => *inter,
-                Err(_) => return,
-            };
-            if event.button == MouseButton::Right
-                && viewmodel.holding() != ViewModelHold::Empty
-                && interact_typ != Interactable::TERMINAL
-            {
-                let ground_item_position = transforms.get(interacting_ent).unwrap();
-                let viewmodel_held: Entity = *vm_children.get(0).unwrap();
-
-                let new_view_type = match interact_typ.itype() {
-                    InteractableType::Hammer => ViewModelHold::Hammer,
-                    InteractableType::LineOfCode => ViewModelHold::LoC,
-                    InteractableType::LineOfCodeGlobule => ViewModelHold::LoCBundle,
-                    InteractableType::Terminal => return,
-                };
-
-                let interact_type = match viewmodel.holding() {
-                    ViewModelHold::Empty => return,
-                    ViewModelHold::Hammer => Interactable::HAMMER,
-                    ViewModelHold::LoCBundle => Interactable::LOCG,
-                    ViewModelHold::LoC => Interactable::LOC,
-                };
+        let mut planks = match ordered_children.get_mut(event.with) {
+            Ok(p) if !p.0.is_empty() => p,
+            _ => return,
+        };
+        let bundle_trans = global_transforms.get(event.with).unwrap();
+        let at = planks
+            .slot_at(bundle_trans, event.point)
+            .round()
+            .clamp(0.0, (planks.0.len() - 1) as f32) as usize;
+        let plank = planks.0.remove(at);
+
+        commands.entity(event.with).remove_children(&[plank]);
+        commands
+            .entity(plank)
+            .insert(held_transform(ViewModelHold::LoC));
+        commands.entity(vm_ent).push_children(&[plank]);
+        viewmodel.change_holding(ViewModelHold::LoC);
+
+        // a bundle of one is just a plank
+        if planks.0.len() == 1 {
+            let last = planks.0.remove(0);
+            let ground_trans = *transforms.get(event.with).unwrap();
+            commands.entity(event.with).remove_children(&[last]);
+            commands
+                .entity(last)
+                .insert_bundle(TransformBundle::from_transform(ground_trans))
+                .insert(Interactable::LOC)
+                .insert(RigidBody::Dynamic)
+                .insert(ActiveCollisionTypes::all())
+                .insert(interactable_dynamic_body());
+            commands.entity(event.with).despawn();
+        } else {
+            planks.relayout(&mut commands, event.with, &colliders);
+        }
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: InteractSoundType::Pickup,
+        });
+        player_state.change_state(PlayerState::Holding);
+    }
+}
 
-                let transgender = match new_view_type {
-                    ViewModelHold::Empty => return,
-                    ViewModelHold::Hammer => {
-                        let mut new_trans = Transform::from_xyz(-0.2, 0.3, 0.0);
-                        new_trans.rotate_local_y(-1.57);
-                        new_trans.rotate_local_x(0.3491);
-                        new_trans.rotate_local_z(-0.1745329);
-                        new_trans
-                    }
-                    ViewModelHold::LoC => {
-                        let mut new_trans = Transform::from_xyz(-0.2, 0.5, 0.0);
-                        new_trans.rotate_local_y(1.57);
-                        new_trans.rotate_local_x(-0.1745329);
-                        new_trans.rotate_local_z(0.3491);
-                        new_trans
-                    }
-                    ViewModelHold::LoCBundle => {
-                        let mut new_trans = Transform::from_xyz(-0.2, 0.3, 0.0);
-                        new_trans.rotate_local_y(-1.57);
-                        new_trans.rotate_local_x(2.967);
-                        new_trans.rotate_local_z(-1.);
-                        new_trans
-                    }
-                };
-                viewmodel.change_holding(new_view_type);
-
-                commands.entity(vm_ent).remove_children(&[viewmodel_held]);
-                commands
-                    .entity(viewmodel_held)
-                    .insert_bundle(TransformBundle::from_transform(*ground_item_position))
-                    .insert(RigidBody::Dynamic)
-                    .insert(ActiveCollisionTypes::all())
-                    .insert(interactable_dynamic_body())
-                    .insert(interact_type);
-                let new_hold = commands
-                    .entity(interacting_ent)
-                    .remove::<Interactable>()
-                    .remove::<RigidBody>()
-                    .remove::<ActiveCollisionTypes>()
-                    .remove::<CollisionGroups>()
-                    .remove::<Transform>()
-                    .insert(transgender)
-                    .insert(ActiveCollisionTypes::empty())
-                    .insert(none())
-                    .insert(RigidBody::Fixed)
-                    .id();
-                commands.entity(vm_ent).push_children(&[new_hold]);
-                interact_sfx_event.send(InteractSoundEvent {
-                    int_type: InteractSoundType::Pickup,
-                });
-                lock.i_ran_dawddy();
-            }
+/// where something sits in the player's hand
+pub fn held_transform(hold: ViewModelHold) -> Transform {
+    match hold {
+        ViewModelHold::Empty => Transform::default(),
+        ViewModelHold::Hammer => {
+            let mut new_trans = Transform::from_xyz(-0.2, 0.3, 0.0);
+            new_trans.rotate_local_y(-1.57);
+            new_trans.rotate_local_x(0.3491);
+            new_trans.rotate_local_z(-0.1745329);
+            new_trans
+        }
+        ViewModelHold::LoC => {
+            let mut new_trans = Transform::from_xyz(-0.2, 0.5, 0.0);
+            new_trans.rotate_local_y(1.57);
+            new_trans.rotate_local_x(-0.1745329);
+            new_trans.rotate_local_z(0.3491);
+            new_trans
+        }
+        ViewModelHold::LoCBundle => {
+            let mut new_trans = Transform::from_xyz(-0.2, 0.3, 0.0);
+            new_trans.rotate_local_y(-1.57);
+            new_trans.rotate_local_x(2.967);
+            new_trans.rotate_local_z(-1.);
+            new_trans
         }
     }
-    pub fn clear_reader_because_fuck_you(reader: EventReader<MouseInteraction>) {
-        reader.clear()
+}
+
+/// what something in the player's hand is once it's let go of
+pub fn held_interactable(hold: ViewModelHold) -> Option<Interactable> {
+    match hold {
+        ViewModelHold::Empty => None,
+        ViewModelHold::Hammer => Some(Interactable::HAMMER),
+        ViewModelHold::LoCBundle => Some(Interactable::LOCG),
+        ViewModelHold::LoC => Some(Interactable::LOC),
     }
-    // throw if right click
-    //     // if left click: swing hammer, or drop
-    //     match pressed.peek().unwrap() {
-    //         // safe since we know one has been pressed at least
-    //         // S W I N G
-    //         MouseButton::Left => {
-    //             match viewmodel.holding() {
-    //                 ViewModelHold::Hammer => {
-    //                     // swing
-    //                 }
-    //                 ViewModelHold::Empty => (),
-    //                 _ => { //drop
-    //                 }
-    //             }
-    //         }
-    //         // Y E E T held item
-    //         MouseButton::Right => {
-    //             println!("YEET (TODO!)");
-    //             viewmodel.change_holding(ViewModelHold::Empty);
-    //             commands.entity(vm_ent).remove_children(&[vm_child_id]);
-    //         }
-    //         _ => (),
-    //     }
-
-    // TODO
-    // pub fn interact_mbleft_holding_hammer() {}
-    // pub fn interact_mbleft_holding_nonhammer() {}
-    // pub fn interact_mbright() {}
 }
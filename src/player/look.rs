@@ -1,5 +1,7 @@
+use crate::bindings::Actions;
 use crate::config::PlayerConfig;
 use crate::player::fsm::PlayerStateMachine;
+use crate::player::grab::PhysicsGrab;
 use crate::player::{Player, PlayerCamera};
 use crate::prelude::*;
 use crate::viewmodel::ViewModel;
@@ -21,7 +23,8 @@ pub fn build(app: &mut App) {
         .add_system(
             Player::look
                 .run_in_state(GameState::InOffice)
-                .run_unless_resource_equals(PlayerStateMachine::INTERACTING),
+                .run_unless_resource_equals(PlayerStateMachine::INTERACTING)
+                .run_if_not(PhysicsGrab::is_rotating),
         )
         .add_system(Player::sync_locations.run_in_state(GameState::InOffice));
 }
@@ -30,6 +33,8 @@ impl Player {
     pub fn look(
         config: Res<PlayerConfig>,
         windows: Res<Windows>,
+        time: Res<Time>,
+        actions: Res<Actions>,
         mut state: ResMut<MouseInputState>,
         motion: Res<Events<MouseMotion>>,
         mut query: Query<&mut Transform, With<PlayerCamera>>,
@@ -53,6 +58,16 @@ impl Player {
             player_trans.rotation = Quat::from_axis_angle(Vec3::Y, delta_state.yaw)
                 * Quat::from_axis_angle(Vec3::X, delta_state.pitch);
         }
+
+        // the look stick turns at a steady rate rather than by how far it moved
+        if actions.look != Vec2::ZERO {
+            let turn = actions.look * config.stick_sens * time.delta_seconds();
+            delta_state.pitch = (delta_state.pitch + turn.y).clamp(-1.54, 1.54);
+            delta_state.yaw -= turn.x;
+
+            player_trans.rotation = Quat::from_axis_angle(Vec3::Y, delta_state.yaw)
+                * Quat::from_axis_angle(Vec3::X, delta_state.pitch);
+        }
     }
 
     pub fn sync_locations(
@@ -1,4 +1,5 @@
 use crate::audio::events::{InteractSoundEvent, InteractSoundType};
+use crate::bindings::{Action, Actions};
 use crate::player::fsm::{PlayerState, PlayerStateMachine};
 use crate::terminal::TerminalCommand;
 use crate::{config::PlayerConfig, prelude::*};
@@ -21,7 +22,7 @@ pub fn build(app: &mut App) {
 
 impl Player {
     pub fn movement(
-        keys: Res<Input<KeyCode>>,
+        actions: Res<Actions>,
         time: Res<Time>,
         windows: Res<Windows>,
         settings: Res<PlayerConfig>,
@@ -42,14 +43,15 @@ impl Player {
         let fwd = -Vec3::new(local_z.x, 0., local_z.z);
         let right = Vec3::new(local_z.z, 0., -local_z.x);
 
-        let mut vel = Vec3::ZERO;
-        for key in keys.get_pressed() {
-            vel += match key {
-                KeyCode::W => fwd,
-                KeyCode::S => -fwd,
-                KeyCode::A => -right,
-                KeyCode::D => right,
-                _ => continue,
+        let mut vel = fwd * actions.movement.y + right * actions.movement.x;
+        for (action, dir) in [
+            (Action::MoveForward, fwd),
+            (Action::MoveBack, -fwd),
+            (Action::MoveLeft, -right),
+            (Action::MoveRight, right),
+        ] {
+            if actions.pressed(action) {
+                vel += dir;
             }
         }
 
@@ -58,7 +60,8 @@ impl Player {
             _ => 1.0,
         };
 
-        vel = vel.normalize_or_zero()
+        // a stick only part of the way over walks slower
+        vel = vel.clamp_length_max(1.0)
             * time.delta_seconds()
             * settings.mvmnt_speed
             * mvmnt_speed_multi;
@@ -0,0 +1,215 @@
+use crate::audio::events::{InteractSoundEvent, InteractSoundType};
+use crate::bindings::{Action, Actions};
+use crate::{
+    config::PlayerConfig,
+    interactable::{Interactable, InteractableType},
+    phys::group::collide::interactable_dynamic_body,
+    player::{
+        fsm::{PlayerState, PlayerStateMachine},
+        grab::PhysicsGrab,
+        interactions::{held_interactable, AddInteractionRule, HOLDING_SOMETHING},
+        PlayerCamera,
+    },
+    prelude::{phys::*, *},
+    shop::Upgrades,
+    ui::{ThrowChargeBar, ThrowChargeFill},
+    viewmodel::{ViewModel, ViewModelHold},
+};
+
+/// let go of throw sooner than this and the item is just put down
+const TAP_SECS: f32 = 0.2;
+/// how long throw has to be held for a full strength throw
+const FULL_CHARGE_SECS: f32 = 1.0;
+
+pub fn build(app: &mut App) {
+    app.init_resource::<ThrowCharge>()
+        .add_interaction_rule(
+            Action::Throw,
+            HOLDING_SOMETHING,
+            &[None],
+            ThrowCharge::start,
+        )
+        .add_system(ThrowCharge::release.run_in_state(GameState::InOffice))
+        .add_system(ThrowCharge::update_ui.run_in_state(GameState::InOffice));
+}
+
+/// A throw being wound up by holding the throw button, it goes when the button comes back up.
+/// Shared by what's in hand and what's held with the physics grab
+#[derive(Default)]
+pub struct ThrowCharge {
+    /// seconds throw has been held for, `None` when not charging
+    held_for: Option<f32>,
+    /// what's being thrown, letting go of it or switching to something else cancels the throw
+    item: Option<Entity>,
+}
+
+impl ThrowCharge {
+    /// 0 for a tap, rising to 1 at `FULL_CHARGE_SECS`
+    pub fn strength(&self) -> f32 {
+        match self.held_for {
+            Some(t) if t >= TAP_SECS => (t / FULL_CHARGE_SECS).min(1.0),
+            _ => 0.0,
+        }
+    }
+
+    pub fn begin(&mut self, item: Entity) {
+        self.held_for = Some(0.0);
+        self.item = Some(item);
+    }
+
+    fn cancel(&mut self) {
+        *self = ThrowCharge::default();
+    }
+
+    fn start(mut charge: ResMut<ThrowCharge>, viewmodel_query: Query<&Children, With<ViewModel>>) {
+        let held = viewmodel_query
+            .get_single()
+            .ok()
+            .and_then(|c| c.get(0))
+            .copied();
+        if let Some(item) = held {
+            charge.begin(item);
+        }
+    }
+
+    /// Winds the throw up while the button is down and lets go of the held item once it's up.
+    /// A tap drops it in front of the player instead of throwing it
+    fn release(
+        mut commands: Commands,
+        mut charge: ResMut<ThrowCharge>,
+        mut grab: ResMut<PhysicsGrab>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        time: Res<Time>,
+        actions: Res<Actions>,
+        config: Res<PlayerConfig>,
+        upgrades: Res<Upgrades>,
+        mut viewmodel_query: Query<(&mut ViewModel, Entity, Option<&Children>)>,
+        camera_query: Query<&Transform, With<PlayerCamera>>,
+        player_query: Query<&Velocity, With<Player>>,
+        children: Query<&Children>,
+    ) {
+        let held_for = match charge.held_for {
+            Some(t) => t,
+            None => return,
+        };
+        let (mut viewmodel, vm_ent, vm_children) = match viewmodel_query.get_single_mut() {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        let in_hand = vm_children
+            .and_then(|c| c.get(0))
+            .copied()
+            .zip(held_interactable(viewmodel.holding()));
+        let grabbed = grab.held.zip(grab.interactable());
+        let (child, interact_type) = match (in_hand, grabbed) {
+            (Some(item), _) | (None, Some(item))
+                if *player_state != PlayerStateMachine::INTERACTING
+                    && charge.item == Some(item.0) =>
+            {
+                item
+            }
+            // it was let go of some other way, or switched away from on the hotbar
+            _ => {
+                charge.cancel();
+                return;
+            }
+        };
+        if actions.pressed(Action::Throw) {
+            charge.held_for = Some(held_for + time.delta_seconds());
+            return;
+        }
+        let strength = charge.strength();
+        charge.cancel();
+
+        let camera_trans = camera_query.single();
+        let force_extra_multi = match interact_type.itype() {
+            InteractableType::Hammer => 4.0,
+            InteractableType::LineOfCode => 1.0,
+            InteractableType::LineOfCodeGlobule => match children.get(child) {
+                Ok(c) => c.len() as f32,
+                Err(_) => 1.0,
+            },
+            InteractableType::Terminal => return,
+        };
+        let impulse = camera_trans.rotation
+            * -Vec3::Z
+            * config.max_throw_impulse
+            * strength
+            * force_extra_multi
+            * upgrades.throw_multiplier();
+
+        if grab.held == Some(child) {
+            grab.let_go(&mut commands, impulse);
+        } else {
+            let player_vel = player_query.get_single().copied().unwrap_or_default();
+            Self::throw_from_hand(
+                &mut commands,
+                camera_trans,
+                player_vel,
+                vm_ent,
+                child,
+                interact_type,
+                impulse,
+            );
+            viewmodel.change_holding(ViewModelHold::Empty);
+        }
+        player_state.change_state(PlayerState::Idle);
+        interact_sfx_event.send(InteractSoundEvent {
+            int_type: match strength > 0.0 {
+                true => InteractSoundType::Throw,
+                false => InteractSoundType::Attach,
+            },
+        });
+    }
+
+    /// the held item leaves the viewmodel just in front of the camera
+    fn throw_from_hand(
+        commands: &mut Commands,
+        camera_trans: &Transform,
+        player_vel: Velocity,
+        vm_ent: Entity,
+        child: Entity,
+        interact_type: Interactable,
+        impulse: Vec3,
+    ) {
+        // new tranform
+        let vm_trans = Vec3::new(0.0, 0.0, -1.0);
+        let c_rot = camera_trans.rotation;
+        let fin = ((c_rot * vm_trans).normalize_or_zero() * 2.0) + camera_trans.translation;
+
+        commands.entity(vm_ent).remove_children(&[child]);
+        commands
+            .entity(child)
+            .insert_bundle(TransformBundle::from_transform(
+                Transform::from_translation(fin),
+            ))
+            .insert(RigidBody::Dynamic)
+            .insert(ActiveCollisionTypes::all())
+            .insert(interactable_dynamic_body())
+            .insert(ExternalImpulse {
+                impulse,
+                ..Default::default()
+            })
+            .insert(interact_type)
+            .insert(player_vel);
+    }
+
+    /// the bar under the crosshair only shows once it's more than a tap
+    fn update_ui(
+        charge: Res<ThrowCharge>,
+        mut bar: Query<&mut UiColor, With<ThrowChargeBar>>,
+        mut fill: Query<&mut Style, With<ThrowChargeFill>>,
+    ) {
+        let strength = charge.strength();
+        if let Ok(mut color) = bar.get_single_mut() {
+            *color = match strength > 0.0 {
+                true => Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                false => Color::NONE.into(),
+            };
+        }
+        if let Ok(mut style) = fill.get_single_mut() {
+            style.size.width = Val::Percent(strength * 100.0);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{
+    code::LoCBlock,
+    interactable::{Interactable, InteractableType},
+    prelude::{phys::*, *},
+};
+
+/// how close a plank has to come to a free slot to be pulled into it
+const SNAP_DIST: f32 = 0.3;
+/// planks moving faster than this fly past instead of snapping, so throws aren't caught
+const SNAP_MAX_SPEED: f32 = 2.0;
+
+pub struct RackPlugin;
+
+impl Plugin for RackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(Rack::snap.run_in_state(GameState::InOffice));
+    }
+}
+
+/// Somewhere a plank can be put down neatly, in world space
+#[derive(Debug, Clone)]
+pub struct RackSlot {
+    pub trans: Transform,
+    pub plank: Option<Entity>,
+}
+
+/// A shelf that holds loose planks in fixed slots
+///
+/// named `rack_<name>` in the glTF, with an empty child for each slot
+/// (named `slot_<n>`) placed and rotated the way a plank should sit in it
+#[derive(Component, Debug, Clone)]
+pub struct Rack {
+    pub slots: Vec<RackSlot>,
+}
+
+impl Rack {
+    pub fn new(slots: impl IntoIterator<Item = Transform>) -> Self {
+        Self {
+            slots: slots
+                .into_iter()
+                .map(|trans| RackSlot { trans, plank: None })
+                .collect(),
+        }
+    }
+
+    /// the nearest free slot within `SNAP_DIST` of `point`
+    fn free_slot_near(&self, point: Vec3) -> Option<(usize, f32)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.plank.is_none())
+            .map(|(i, slot)| (i, slot.trans.translation.distance(point)))
+            .filter(|(_, dist)| *dist < SNAP_DIST)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// A slot is free again once its plank is picked up, bundled or despawned with the level
+    fn free_slots(&mut self, planks: &Query<(&Interactable, &RigidBody)>) {
+        for slot in self.slots.iter_mut() {
+            let plank = match slot.plank {
+                Some(p) => p,
+                None => continue,
+            };
+            if !matches!(planks.get(plank), Ok((_, RigidBody::Fixed))) {
+                slot.plank = None;
+            }
+        }
+    }
+
+    /// Puts loose planks that come to rest near a free slot into it, fixed in place
+    fn snap(
+        mut commands: Commands,
+        mut racks: Query<&mut Rack>,
+        bodies: Query<(&Interactable, &RigidBody)>,
+        planks: Query<
+            (
+                Entity,
+                &Transform,
+                &Interactable,
+                &RigidBody,
+                Option<&Velocity>,
+            ),
+            (With<LoCBlock>, Without<Parent>),
+        >,
+    ) {
+        for mut rack in racks.iter_mut() {
+            rack.free_slots(&bodies);
+        }
+        for (plank, trans, interactable, body, vel) in planks.iter() {
+            if interactable.itype() != InteractableType::LineOfCode
+                || *body != RigidBody::Dynamic
+                || vel.map_or(false, |v| v.linvel.length() > SNAP_MAX_SPEED)
+            {
+                continue;
+            }
+            let nearest = racks
+                .iter_mut()
+                .filter_map(|rack| {
+                    let (i, dist) = rack.free_slot_near(trans.translation)?;
+                    Some((rack, i, dist))
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2));
+            let (mut rack, i, _) = match nearest {
+                Some(n) => n,
+                None => continue,
+            };
+            let slot = &mut rack.slots[i];
+            slot.plank = Some(plank);
+            commands
+                .entity(plank)
+                .insert(slot.trans)
+                .insert(RigidBody::Fixed)
+                .insert(Velocity::zero());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        audio::events::InteractSoundEvent,
+        bindings::Action,
+        code::LocType,
+        player::{
+            fsm::PlayerStateMachine,
+            grab::PhysicsGrab,
+            interactions::{MouseInteraction, PendingInteraction},
+            PlayerCamera,
+        },
+        viewmodel::{ViewModel, ViewModelHold},
+    };
+    use bevy::ecs::schedule::IntoSystemDescriptor;
+
+    /// a rack with one slot at the origin, holding a plank
+    fn racked_plank() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.init_resource::<PhysicsGrab>();
+        world.init_resource::<PlayerStateMachine>();
+        world.init_resource::<Events<InteractSoundEvent>>();
+        world
+            .spawn()
+            .insert(PlayerCamera)
+            .insert(Transform::default());
+        world.spawn().insert(ViewModel {
+            holding: ViewModelHold::Empty,
+        });
+        let plank = world
+            .spawn()
+            .insert(LoCBlock {
+                line_of_code: "}".to_owned(),
+                loc_type: LocType::Neutral,
+            })
+            .insert(Interactable::LOC)
+            .insert(RigidBody::Fixed)
+            .insert(Transform::default())
+            .id();
+        let mut rack = Rack::new([Transform::default()]);
+        rack.slots[0].plank = Some(plank);
+        let rack = world.spawn().insert(rack).id();
+        (world, plank, rack)
+    }
+
+    fn run<Params>(world: &mut World, system: impl IntoSystemDescriptor<Params>) {
+        let mut stage = SystemStage::single_threaded();
+        stage.add_system(system);
+        stage.run(world);
+    }
+
+    fn slot(world: &World, rack: Entity) -> Option<Entity> {
+        world.get::<Rack>(rack).unwrap().slots[0].plank
+    }
+
+    #[test]
+    fn grabbed_plank_leaves_its_slot() {
+        let (mut world, plank, rack) = racked_plank();
+        world.insert_resource(PendingInteraction::clicked(Action::Interact, plank));
+        run(&mut world, PhysicsGrab::grab);
+        run(&mut world, Rack::snap);
+        assert_eq!(world.get::<RigidBody>(plank), Some(&RigidBody::Dynamic));
+        assert_eq!(slot(&world, rack), None);
+
+        // let go right where it was, so it goes back in
+        run(
+            &mut world,
+            |mut commands: Commands, mut grab: ResMut<PhysicsGrab>| {
+                grab.let_go(&mut commands, Vec3::ZERO)
+            },
+        );
+        run(&mut world, Rack::snap);
+        assert_eq!(world.get::<RigidBody>(plank), Some(&RigidBody::Fixed));
+        assert_eq!(slot(&world, rack), Some(plank));
+    }
+
+    #[test]
+    fn picked_up_plank_leaves_its_slot() {
+        let (mut world, plank, rack) = racked_plank();
+        world.insert_resource(PendingInteraction::clicked(Action::Interact, plank));
+        run(&mut world, MouseInteraction::pick_up);
+        run(&mut world, Rack::snap);
+        assert!(world.get::<Parent>(plank).is_some());
+        assert_eq!(slot(&world, rack), None);
+
+        // and another plank can have it
+        let other = world
+            .spawn()
+            .insert(LoCBlock {
+                line_of_code: "{".to_owned(),
+                loc_type: LocType::Neutral,
+            })
+            .insert(Interactable::LOC)
+            .insert(RigidBody::Dynamic)
+            .insert(Transform::from_xyz(0.1, 0.0, 0.0))
+            .id();
+        run(&mut world, Rack::snap);
+        assert_eq!(slot(&world, rack), Some(other));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    code::{LineOfCode, LoCBlock},
+    interactable::Interactable,
+    level::NewLevel,
+    office::SceneLocations,
+    phys::group::collide::interactable_dynamic_body,
+    player::interactions::OrderedChildren,
+    prelude::{phys::*, *},
+    terminal::TerminalCommand,
+    unresult_or_continue,
+};
+use bevy::utils::HashMap;
+
+/// where restored planks are dropped, falls back to where the level spawns
+const BIN_LOCATIONS: &[&str] = &["point3d_recyclebin", "point3d_spawn"];
+
+pub struct RecyclePlugin;
+
+impl Plugin for RecyclePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RecycleBin>()
+            .add_enter_system(GameState::InOffice, RecycleBin::reset)
+            .add_system(RecycleBin::empty_on_new_level.run_in_state(GameState::InOffice))
+            .add_system(RecycleBin::undo.run_in_state(GameState::InOffice));
+    }
+}
+
+/// A deleted plank, hidden and out of the physics world until it's restored
+#[derive(Debug, Clone)]
+pub struct Binned {
+    pub plank: Entity,
+    pub block: LoCBlock,
+    pub collider: Collider,
+}
+
+/// Everything the deleter took this level, oldest first
+#[derive(Debug, Clone, Default)]
+pub struct RecycleBin {
+    pub binned: Vec<Binned>,
+}
+
+impl RecycleBin {
+    /// Takes the plank out of the world, bundles have to be broken up
+    /// with `throw_away_bundle` first
+    pub fn throw_away(
+        &mut self,
+        commands: &mut Commands,
+        plank: Entity,
+        block: LoCBlock,
+        collider: Collider,
+    ) {
+        commands
+            .entity(plank)
+            .remove::<Collider>()
+            .remove::<RigidBody>()
+            .remove::<Interactable>()
+            .insert(Visibility { is_visible: false });
+        self.binned.push(Binned {
+            plank,
+            block,
+            collider,
+        });
+    }
+
+    pub fn throw_away_bundle(
+        &mut self,
+        commands: &mut Commands,
+        bundle: Entity,
+        planks: &OrderedChildren,
+        blocks: &Query<(&LoCBlock, &Collider)>,
+    ) {
+        commands.entity(bundle).remove_children(&planks.0);
+        for plank in planks.0.iter() {
+            let (block, collider) = unresult_or_continue!(blocks.get(*plank));
+            self.throw_away(commands, *plank, block.clone(), collider.clone());
+        }
+        commands.entity(bundle).despawn();
+    }
+
+    /// Copies of lines in the bin that the correct answer still needs,
+    /// i.e. only the ones missing from `planks` (every plank in the level, binned ones are skipped)
+    pub fn required<'a>(
+        &self,
+        correct: &[LineOfCode],
+        planks: impl IntoIterator<Item = (Entity, &'a LoCBlock)>,
+    ) -> usize {
+        let mut needed = HashMap::<&str, usize>::new();
+        for loc in correct {
+            *needed.entry(loc.code.as_str()).or_default() += 1;
+        }
+        for (plank, block) in planks {
+            if self.binned.iter().any(|b| b.plank == plank) {
+                continue;
+            }
+            if let Some(n) = needed.get_mut(block.line_of_code.as_str()) {
+                *n = n.saturating_sub(1);
+            }
+        }
+        let mut binned = HashMap::<&str, usize>::new();
+        for b in self.binned.iter() {
+            *binned.entry(b.block.line_of_code.as_str()).or_default() += 1;
+        }
+        binned
+            .into_iter()
+            .map(|(line, n)| n.min(needed.get(line).copied().unwrap_or(0)))
+            .sum()
+    }
+
+    /// what the `bin` command prints
+    pub fn listing(&self) -> String {
+        if self.binned.is_empty() {
+            return "recycle bin is empty".to_owned();
+        }
+        let mut out = format!("recycle bin: {} lines", self.binned.len());
+        for b in self.binned.iter().rev() {
+            out.push_str(&format!("\n  {}", b.block.line_of_code));
+        }
+        out.push_str("\nuse `undo` to restore the newest one");
+        out
+    }
+
+    fn reset(mut bin: ResMut<RecycleBin>) {
+        bin.binned.clear();
+    }
+
+    fn empty_on_new_level(
+        mut bin: ResMut<RecycleBin>,
+        mut new_level: EventReader<NewLevel>,
+        mut term_cmds: EventReader<TerminalCommand>,
+    ) {
+        // the planks themselves get despawned along with the rest of the level,
+        // a restart does that straight away so don't wait for its `NewLevel`
+        let restarted = term_cmds.iter().any(|c| *c == TerminalCommand::Restart);
+        if new_level.iter().count() > 0 || restarted {
+            bin.binned.clear();
+        }
+    }
+
+    fn undo(
+        mut commands: Commands,
+        mut bin: ResMut<RecycleBin>,
+        mut term_cmds: EventReader<TerminalCommand>,
+        locations: Res<SceneLocations>,
+    ) {
+        let undos = term_cmds
+            .iter()
+            .filter(|c| **c == TerminalCommand::Undo)
+            .count();
+        for _ in 0..undos {
+            let binned = match bin.binned.pop() {
+                Some(b) => b,
+                None => return,
+            };
+            let mut trans = BIN_LOCATIONS
+                .iter()
+                .find_map(|name| locations.locations.get(name))
+                .copied()
+                .unwrap_or_default();
+            trans.translation.y += 0.2;
+            commands
+                .entity(binned.plank)
+                .insert_bundle(TransformBundle::from_transform(trans))
+                .insert(Visibility { is_visible: true })
+                .insert(binned.collider)
+                .insert(RigidBody::Dynamic)
+                .insert(ActiveCollisionTypes::all())
+                .insert(interactable_dynamic_body())
+                .insert(Interactable::LOC);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code::{CodeColor, Diff, LocType};
+
+    fn loc(code: &str) -> LineOfCode {
+        LineOfCode {
+            diff: Diff::Eq,
+            color: CodeColor::None,
+            code: code.to_owned(),
+            typo: None,
+        }
+    }
+
+    fn block(code: &str) -> LoCBlock {
+        LoCBlock {
+            line_of_code: code.to_owned(),
+            loc_type: LocType::Neutral,
+        }
+    }
+
+    fn bin(planks: &[(Entity, LoCBlock)]) -> RecycleBin {
+        RecycleBin {
+            binned: planks
+                .iter()
+                .map(|(plank, block)| Binned {
+                    plank: *plank,
+                    block: block.clone(),
+                    collider: Collider::ball(1.0),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn spare_copies_in_play_cover_binned_ones() {
+        let planks = [
+            (Entity::from_raw(0), block("}")),
+            (Entity::from_raw(1), block("}")),
+        ];
+        let bin = bin(&planks[..1]);
+        let correct = [loc("}")];
+        assert_eq!(
+            bin.required(&correct, planks.iter().map(|(e, b)| (*e, b))),
+            0
+        );
+    }
+
+    #[test]
+    fn only_missing_copies_count() {
+        let planks = [
+            (Entity::from_raw(0), block("}")),
+            (Entity::from_raw(1), block("}")),
+            (Entity::from_raw(2), block("}")),
+            (Entity::from_raw(3), block("fn main() {")),
+        ];
+        let bin = bin(&planks[..2]);
+        let correct = [loc("fn main() {"), loc("}"), loc("}")];
+        assert_eq!(
+            bin.required(&correct, planks.iter().map(|(e, b)| (*e, b))),
+            1
+        );
+    }
+
+    #[test]
+    fn lines_not_in_the_answer_are_free_to_delete() {
+        let planks = [(Entity::from_raw(0), block("junk();"))];
+        let bin = bin(&planks);
+        let correct = [loc("}")];
+        assert_eq!(
+            bin.required(&correct, planks.iter().map(|(e, b)| (*e, b))),
+            0
+        );
+    }
+}
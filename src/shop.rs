@@ -0,0 +1,357 @@
+use crate::{
+    code::LoCBlock,
+    level::{JobResult, LevelTimer, Levels, NewLevel, Submitted},
+    player::interactions::OrderedChildren,
+    prelude::{phys::*, *},
+    terminal::{ansi, TermWrite, TerminalCommand},
+    tools::level_order,
+    viewmodel::ViewModel,
+    TotalPoints,
+};
+use std::time::Duration;
+
+/// seconds added to every job for each `ExtraTime` bought
+const EXTRA_TIME_SECS: u64 = 30;
+/// how much stronger each `StrongerThrow` makes a throw
+const THROW_STEP: f32 = 0.5;
+
+pub struct ShopPlugin;
+
+impl Plugin for ShopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Upgrades>()
+            .add_enter_system(GameState::InOffice, Upgrades::reset)
+            .add_system(Upgrades::open_between_jobs.run_in_state(GameState::InOffice))
+            .add_system(Upgrades::buy.run_in_state(GameState::InOffice))
+            .add_system(Upgrades::auto_sort.run_in_state(GameState::InOffice));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Upgrade {
+    ExtraTime,
+    StrongerThrow,
+    BundlePaint,
+    AutoSort,
+}
+
+/// in the order the shop lists them, `buy n` takes an index into this starting from 1
+const UPGRADES: &[Upgrade] = &[
+    Upgrade::ExtraTime,
+    Upgrade::StrongerThrow,
+    Upgrade::BundlePaint,
+    Upgrade::AutoSort,
+];
+
+impl Upgrade {
+    pub fn name(&self) -> &'static str {
+        use Upgrade::*;
+        match self {
+            ExtraTime => "overtime",
+            StrongerThrow => "gym membership",
+            BundlePaint => "wide brush",
+            AutoSort => "auto-sort",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        use Upgrade::*;
+        match self {
+            ExtraTime => "+30s on every job",
+            StrongerThrow => "throw planks and bundles further",
+            BundlePaint => "painters colour a whole bundle at once",
+            AutoSort => "one use, `sort` puts the bundle you're holding in order",
+        }
+    }
+
+    pub fn price(&self) -> f64 {
+        use Upgrade::*;
+        match self {
+            ExtraTime => 3000.,
+            StrongerThrow => 2000.,
+            BundlePaint => 5000.,
+            AutoSort => 2500.,
+        }
+    }
+
+    /// how many times it can be bought, `None` for no limit
+    pub fn max(&self) -> Option<u32> {
+        use Upgrade::*;
+        match self {
+            ExtraTime | StrongerThrow => Some(3),
+            BundlePaint => Some(1),
+            AutoSort => None,
+        }
+    }
+}
+
+/// What's been bought with points, consulted by the tools and interactions
+#[derive(Debug, Clone, Default)]
+pub struct Upgrades {
+    pub extra_time: u32,
+    pub throw_strength: u32,
+    pub bundle_paint: bool,
+    /// auto-sorts bought and not used yet
+    pub auto_sorts: u32,
+    /// see `open_between_jobs`
+    open: bool,
+}
+
+impl Upgrades {
+    pub fn extra_time(&self) -> Duration {
+        Duration::from_secs(EXTRA_TIME_SECS * self.extra_time as u64)
+    }
+
+    pub fn throw_multiplier(&self) -> f32 {
+        1.0 + THROW_STEP * self.throw_strength as f32
+    }
+
+    fn bought(&self, upgrade: Upgrade) -> u32 {
+        use Upgrade::*;
+        match upgrade {
+            ExtraTime => self.extra_time,
+            StrongerThrow => self.throw_strength,
+            BundlePaint => self.bundle_paint as u32,
+            // used up, so never sold out
+            AutoSort => 0,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// The upgrade `buy n` would get, or why it can't be bought
+    fn check(&self, n: usize, points: &TotalPoints) -> Result<Upgrade, String> {
+        if !self.is_open() {
+            return Err("the shop is closed until this job is done".to_owned());
+        }
+        let upgrade = n
+            .checked_sub(1)
+            .and_then(|i| UPGRADES.get(i))
+            .copied()
+            .ok_or_else(|| format!("no upgrade {n}, use `shop` to see what's for sale"))?;
+        if matches!(upgrade.max(), Some(max) if self.bought(upgrade) >= max) {
+            return Err(format!("{} is sold out", upgrade.name()));
+        }
+        if points.sum < upgrade.price() {
+            return Err(format!(
+                "{} costs {} points, you have {}",
+                upgrade.name(),
+                upgrade.price() as u64,
+                points.sum as u64
+            ));
+        }
+        Ok(upgrade)
+    }
+
+    /// what the `shop` command prints
+    pub fn listing(&self, points: &TotalPoints) -> String {
+        let mut out = format!("shop: you have {} points", points.sum as u64);
+        if !self.is_open() {
+            out.push_str(&format!(
+                "\n{}closed until this job is done{}",
+                ansi::YELLOW,
+                ansi::RESET
+            ));
+        }
+        for (i, upgrade) in UPGRADES.iter().enumerate() {
+            let stock = match upgrade.max() {
+                Some(max) if self.bought(*upgrade) >= max => "sold out".to_owned(),
+                Some(max) => format!("{}/{max}", self.bought(*upgrade)),
+                None => format!("{} left", self.auto_sorts),
+            };
+            out.push_str(&format!(
+                "\n  {}. {} - {} points ({stock})\n     {}",
+                i + 1,
+                upgrade.name(),
+                upgrade.price() as u64,
+                upgrade.description()
+            ));
+        }
+        out.push_str("\nuse `buy <n>` to buy one");
+        out
+    }
+
+    fn reset(mut upgrades: ResMut<Upgrades>) {
+        *upgrades = Upgrades::default();
+    }
+
+    /// Opens the shop when a job is handed in. It closes again once the next job is under way,
+    /// i.e. something gets scanned for it or it's restarted. Handing a job in also starts
+    /// the next one, so a `NewLevel` that comes with a `JobResult` leaves it open
+    fn open_between_jobs(
+        mut upgrades: ResMut<Upgrades>,
+        mut new_level: EventReader<NewLevel>,
+        mut results: EventReader<JobResult>,
+        subs: Res<Submitted>,
+    ) {
+        let started = new_level.iter().count() > 0;
+        if results.iter().count() > 0 {
+            upgrades.open = true;
+        } else if started || (subs.is_changed() && subs.last.is_some()) {
+            upgrades.open = false;
+        }
+    }
+
+    /// Buys what `buy n` asks for and tells the player how it went
+    fn buy(
+        mut upgrades: ResMut<Upgrades>,
+        mut term_cmds: EventReader<TerminalCommand>,
+        mut term_write: EventWriter<TermWrite>,
+        mut total_pts: ResMut<TotalPoints>,
+        mut timer: ResMut<LevelTimer>,
+    ) {
+        for cmd in term_cmds.iter() {
+            let n = match cmd {
+                TerminalCommand::Buy(n) => *n,
+                _ => continue,
+            };
+            let upgrade = match upgrades.check(n, &total_pts) {
+                Ok(u) => u,
+                Err(why) => {
+                    term_write.send(TermWrite {
+                        s: format!("\n{why}\n>>"),
+                    });
+                    continue;
+                }
+            };
+            total_pts.sum -= upgrade.price();
+            match upgrade {
+                Upgrade::ExtraTime => {
+                    upgrades.extra_time += 1;
+                    // the job that's about to start gets it too
+                    timer.extend(Duration::from_secs(EXTRA_TIME_SECS));
+                }
+                Upgrade::StrongerThrow => upgrades.throw_strength += 1,
+                Upgrade::BundlePaint => upgrades.bundle_paint = true,
+                Upgrade::AutoSort => upgrades.auto_sorts += 1,
+            }
+            term_write.send(TermWrite {
+                s: format!("\nbought {}\n>>", upgrade.name()),
+            });
+        }
+    }
+
+    /// Puts the bundle in the player's hand into the order the job wants
+    fn auto_sort(
+        mut commands: Commands,
+        mut upgrades: ResMut<Upgrades>,
+        mut term_cmds: EventReader<TerminalCommand>,
+        mut term_write: EventWriter<TermWrite>,
+        levels: Res<Levels>,
+        viewmodel: Query<&Children, With<ViewModel>>,
+        colliders: Query<&Collider>,
+        blocks: Query<&LoCBlock>,
+        mut bundles: Query<&mut OrderedChildren>,
+    ) {
+        for _ in term_cmds.iter().filter(|c| **c == TerminalCommand::Sort) {
+            let held = viewmodel
+                .get_single()
+                .ok()
+                .and_then(|ch| ch.iter().find(|e| bundles.contains(**e)).copied());
+            let s = match (upgrades.auto_sorts, held) {
+                (0, _) => "no auto-sorts left, buy one at the `shop`".to_owned(),
+                (_, None) => "pick up a bundle to sort it".to_owned(),
+                (_, Some(bundle)) => {
+                    let mut planks = bundles.get_mut(bundle).unwrap();
+                    planks.0 = level_order(&planks.0, &levels.levels[levels.current].code, &blocks);
+                    planks.relayout(&mut commands, bundle, &colliders);
+                    upgrades.auto_sorts -= 1;
+                    format!("bundle sorted, {} auto-sorts left", upgrades.auto_sorts)
+                }
+            };
+            term_write.send(TermWrite {
+                s: format!("\n{s}\n>>"),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(sum: f64) -> TotalPoints {
+        TotalPoints { sum, total: sum }
+    }
+
+    fn open_shop() -> Upgrades {
+        Upgrades {
+            open: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn closed_shop_sells_nothing() {
+        let upgrades = Upgrades::default();
+        assert_eq!(
+            upgrades.check(1, &points(1e6)),
+            Err("the shop is closed until this job is done".to_owned())
+        );
+        assert!(upgrades
+            .listing(&points(0.))
+            .contains("closed until this job is done"));
+        assert!(!open_shop().listing(&points(0.)).contains("closed"));
+    }
+
+    #[test]
+    fn numbers_start_from_one() {
+        let upgrades = open_shop();
+        for (i, upgrade) in UPGRADES.iter().enumerate() {
+            assert_eq!(upgrades.check(i + 1, &points(1e6)), Ok(*upgrade));
+        }
+        for n in [0, UPGRADES.len() + 1, usize::MAX] {
+            assert_eq!(
+                upgrades.check(n, &points(1e6)),
+                Err(format!("no upgrade {n}, use `shop` to see what's for sale"))
+            );
+        }
+    }
+
+    #[test]
+    fn sold_out() {
+        let mut upgrades = open_shop();
+        upgrades.throw_strength = 3;
+        upgrades.bundle_paint = true;
+        assert_eq!(
+            upgrades.check(2, &points(1e6)),
+            Err("gym membership is sold out".to_owned())
+        );
+        assert_eq!(
+            upgrades.check(3, &points(1e6)),
+            Err("wide brush is sold out".to_owned())
+        );
+        let listing = upgrades.listing(&points(0.));
+        assert!(listing.contains("gym membership - 2000 points (sold out)"));
+        assert!(listing.contains("overtime - 3000 points (0/3)"));
+    }
+
+    #[test]
+    fn cant_afford() {
+        let upgrades = open_shop();
+        assert_eq!(
+            upgrades.check(1, &points(2999.5)),
+            Err("overtime costs 3000 points, you have 2999".to_owned())
+        );
+        assert_eq!(upgrades.check(1, &points(3000.)), Ok(Upgrade::ExtraTime));
+        // being sold out is said before not having the points
+        let mut upgrades = open_shop();
+        upgrades.bundle_paint = true;
+        assert_eq!(
+            upgrades.check(3, &points(0.)),
+            Err("wide brush is sold out".to_owned())
+        );
+    }
+
+    #[test]
+    fn auto_sorts_never_sell_out() {
+        let mut upgrades = open_shop();
+        upgrades.auto_sorts = 7;
+        assert_eq!(upgrades.check(4, &points(1e6)), Ok(Upgrade::AutoSort));
+        assert!(upgrades
+            .listing(&points(0.))
+            .contains("auto-sort - 2500 points (7 left)"));
+    }
+}
@@ -1,15 +1,25 @@
+use crate::bindings::InputBindings;
 use crate::code::{CodeColor, Diff, LineOfCode, LoCBlock, LoCEntity, LocType};
-use crate::level::{LevelTimer, Levels, NewLevel, Submitted};
+use crate::config::ScoreConfig;
+use crate::level::{JobResult, LevelTimer, Levels, NewLevel, Submitted};
+use crate::mail::Inbox;
 use crate::prelude::*;
+use crate::recycle::RecycleBin;
+use crate::shop::Upgrades;
+use crate::typewriter::Typewriter;
 use crate::ui::UIRoot;
 
+pub mod ansi;
 pub mod conv_cp437;
+mod man;
+mod preview;
 mod text_sprite;
 pub use text_sprite::*;
 mod screen;
 use crate::audio::events::{InteractSoundEvent, InteractSoundType, ScannerSoundEvent};
 use crate::player::fsm::{PlayerState, PlayerStateMachine};
 use crate::TotalPoints;
+pub use preview::ScanPreview;
 pub use screen::TerminalScreenTarget;
 
 mod spawn;
@@ -18,6 +28,7 @@ pub const PROPMPT: &str = ">>";
 pub const TERM_DIM: (f32, f32) = (1280.0, 960.0);
 pub const TERM_W: f32 = TERM_DIM.0;
 pub const TERM_H: f32 = TERM_DIM.1;
+pub const TERMINAL_PREFIX: &str = "interactable_terminal";
 
 pub struct TerminalPlugin;
 
@@ -26,19 +37,22 @@ impl Plugin for TerminalPlugin {
         app.add_event::<TerminalCommand>()
             .add_event::<TermWrite>()
             .add_plugin(TextSpritePlugin)
+            .init_resource::<ActiveTerminal>()
             .add_enter_system(
                 GameState::InOffice,
                 TerminalInput::spawn.label("terminal_spawn"),
             )
-            .add_enter_system(GameState::InOffice, TerminalScreenTarget::set_up_2d)
             .add_system(
                 TerminalInput::take_input
                     .run_in_state(GameState::InOffice)
+                    .run_if_not(Typewriter::is_open)
                     .run_if_resource_equals(PlayerStateMachine::INTERACTING),
             )
             .add_system(TerminalCommand::reset.run_in_state(GameState::InOffice))
             .add_system(TerminalCommand::submit.run_in_state(GameState::InOffice))
             .add_system(TerminalInput::take_write.run_in_state(GameState::InOffice))
+            .add_enter_system(GameState::InOffice, ScanPreview::spawn)
+            .add_system(ScanPreview::update.run_in_state(GameState::InOffice))
             .add_system(TerminalInput::show_or_hide_ui.run_in_state(GameState::InOffice))
             .add_system(TerminalCommand::reset.run_in_state(GameState::InOffice));
     }
@@ -47,25 +61,83 @@ impl Plugin for TerminalPlugin {
 #[derive(Component)]
 pub struct TerminalInput {
     pub user_inp_start: usize,
+    /// `EName` of the interactable this terminal belongs to
+    pub name: String,
+}
+
+/// The terminal the player last walked up to
+/// it gets all the typing and all the `TermWrite`s
+#[derive(Default)]
+pub struct ActiveTerminal {
+    pub name: Option<String>,
+}
+
+impl ActiveTerminal {
+    pub fn is(&self, term: &TerminalInput) -> bool {
+        self.name.as_ref() == Some(&term.name)
+    }
+}
+
+pub const TERM_COLS: usize = (TERM_W / ATLAS_CHAR_W) as usize;
+pub const TERM_ROWS: usize = (TERM_H / ATLAS_CHAR_H) as usize;
+
+impl TextSprite {
+    /// drops rows off the top until the (wrapped) text fits on the screen,
+    /// even if it's all one long line
+    fn scroll_to_fit(&mut self, term: &mut TerminalInput) {
+        let mut scrolled = false;
+        while self.row_count() > TERM_ROWS {
+            self.remove_top_row();
+            scrolled = true;
+        }
+        if scrolled {
+            term.user_inp_start = self.len();
+        }
+    }
+
+    /// Adds whatever was typed this frame, backspace never goes further back than `start`
+    /// Returns true if enter was pressed
+    pub fn take_typing(
+        &mut self,
+        keystrokes: &mut EventReader<ReceivedCharacter>,
+        keys: &Input<KeyCode>,
+        start: usize,
+    ) -> bool {
+        let input = keystrokes
+            .iter()
+            .map(|ev| ev.char)
+            .filter(|ch| conv_cp437::index_of(*ch).is_some())
+            .collect::<String>();
+        if !input.is_empty() {
+            self.add_str(&input);
+        }
+        if keys.just_pressed(KeyCode::Back) && self.len() > start {
+            self.pop();
+        }
+        keys.just_pressed(KeyCode::Return)
+    }
 }
 
 impl TerminalInput {
     fn take_input(
-        mut commands: Commands,
         mut interact_sfx_event: EventWriter<InteractSoundEvent>,
-        mut q_input: Query<(Entity, &mut TextSprite, &mut TerminalInput)>,
+        mut q_input: Query<(&mut TextSprite, &mut TerminalInput)>,
         mut keystrokes: EventReader<ReceivedCharacter>,
         keys: Res<Input<KeyCode>>,
         mut terminal_command: EventWriter<TerminalCommand>,
         levels: Res<Levels>,
+        mut inbox: ResMut<Inbox>,
+        bin: Res<RecycleBin>,
+        active: Res<ActiveTerminal>,
+        upgrades: Res<Upgrades>,
+        total_pts: Res<TotalPoints>,
+        bindings: Res<InputBindings>,
     ) {
-        let (entity, mut text_sprite, mut term) = q_input.single_mut();
-        let input = keystrokes
-            .iter()
-            .map(|ev| ev.char)
-            .filter(|ch| conv_cp437::index_of(*ch).is_some())
-            .collect::<String>();
-        text_sprite.add_str(&input, &mut commands, entity, |_| {});
+        let (mut text_sprite, mut term) = match q_input.iter_mut().find(|(_, t)| active.is(t)) {
+            Some(v) => v,
+            None => return,
+        };
+        let entered = text_sprite.take_typing(&mut keystrokes, &keys, term.user_inp_start);
 
         for _ in keys.get_just_pressed() {
             interact_sfx_event.send(InteractSoundEvent {
@@ -73,11 +145,7 @@ impl TerminalInput {
             });
         }
 
-        if keys.just_pressed(KeyCode::Back) && text_sprite.len() > term.user_inp_start {
-            text_sprite.pop(&mut commands);
-        }
-
-        if keys.just_pressed(KeyCode::Return) {
+        if entered {
             let cmd = text_sprite
                 .text
                 .lines()
@@ -99,45 +167,57 @@ impl TerminalInput {
                 "\n{response}\n{prompt}",
                 response = match term_cmd.clone() {
                     Some(Restart) => "restarting...".to_owned(),
-                    Some(ShowCode) => format!("{}", levels.code_text[levels.current].trim_end()),
+                    Some(ShowCode) => colorize_diff(levels.code_text[levels.current].trim_end()),
                     Some(Send) => "sending off completed code".to_owned(),
                     Some(Exit) => "goodbye git".to_owned(),
-                    Some(Help) => "[c]ode | [r]estart | [e]xit | [f]inish".to_owned(),
+                    Some(Help) => {
+                        "[c]ode | [r]estart | [e]xit | [f]inish | [i]nbox | [u]ndo | bin | shop | tutorial | man <topic>"
+                            .to_owned()
+                    }
+                    Some(Man(topic)) => bindings.fill(&man::lookup(&topic)),
+                    Some(Tutorial) => "starting tutorial...".to_owned(),
+                    Some(TutorialStop) => "tutorial stopped".to_owned(),
+                    Some(ShowInbox) => inbox.listing(),
+                    Some(ReadMail(n)) => inbox.open(n),
+                    Some(Undo) => match bin.binned.last() {
+                        Some(b) => format!("restored {}", b.block.line_of_code),
+                        None => "nothing to undo, the recycle bin is empty".to_owned(),
+                    },
+                    Some(ShowBin) => bin.listing(),
+                    Some(Shop) => upgrades.listing(&total_pts),
+                    Some(Buy(_)) => "buying...".to_owned(),
+                    Some(Sort) => "sorting...".to_owned(),
                     None => format!("command {cmd} not recognised, use help for commands"),
                 },
                 prompt = PROPMPT,
             );
 
-            text_sprite.add_multiline_str(&message, &mut commands, entity);
+            text_sprite.add_multiline_str(&message);
             term.user_inp_start = text_sprite.len();
         }
 
-        let ln_count = text_sprite.text.lines().count();
-        let max_count = (TERM_H / ATLAS_CHAR_H).floor() as usize;
-        if ln_count > max_count {
-            text_sprite.remove_top_lines(&mut commands, entity, ln_count - max_count);
-            term.user_inp_start = text_sprite.len();
-        }
+        text_sprite.scroll_to_fit(&mut term);
     }
 
     fn take_write(
-        mut commands: Commands,
-        mut q_input: Query<(Entity, &mut TextSprite, &mut TerminalInput)>,
+        mut q_input: Query<(&mut TextSprite, &mut TerminalInput)>,
         mut writes: EventReader<TermWrite>,
+        active: Res<ActiveTerminal>,
     ) {
-        let (entity, mut text_sprite, mut term) = q_input.single_mut();
-        for TermWrite { s } in writes.iter() {
-            text_sprite.add_multiline_str(&s, &mut commands, entity);
-            term.user_inp_start = text_sprite.len();
+        let writes = writes.iter().collect::<Vec<_>>();
+        if writes.is_empty() {
+            return;
         }
-
-        writes.clear();
-
-        let ln_count = text_sprite.text.lines().count();
-        let max_count = (TERM_H / ATLAS_CHAR_H).floor() as usize;
-        if ln_count > max_count {
-            text_sprite.remove_top_lines(&mut commands, entity, ln_count - max_count);
-            term.user_inp_start = text_sprite.len();
+        for (mut text_sprite, mut term) in q_input.iter_mut() {
+            // nobody has used a terminal yet, so tell all of them
+            if active.name.is_some() && !active.is(&term) {
+                continue;
+            }
+            for TermWrite { s } in writes.iter() {
+                text_sprite.add_multiline_str(s);
+                term.user_inp_start = text_sprite.len();
+            }
+            text_sprite.scroll_to_fit(&mut term);
         }
     }
 
@@ -152,6 +232,9 @@ impl TerminalInput {
     }
 }
 
+/// A command typed into a terminal. It doesn't say which one: all the terminals share
+/// one command space, so every command acts on the same job, shop and recycle bin
+/// whichever terminal it came from, and replies go to the `ActiveTerminal`
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
 pub enum TerminalCommand {
     Restart,
@@ -159,17 +242,44 @@ pub enum TerminalCommand {
     Send,
     Exit,
     Help,
+    ShowInbox,
+    ReadMail(usize),
+    Man(String),
+    Tutorial,
+    TutorialStop,
+    Undo,
+    ShowBin,
+    Shop,
+    Buy(usize),
+    Sort,
 }
 
 impl TerminalCommand {
     pub fn from_str(s: &str) -> Option<Self> {
+        let s = s.to_ascii_lowercase();
+        if let Some(n) = s.strip_prefix("read ") {
+            return n.trim().parse().ok().map(Self::ReadMail);
+        }
+        if let Some(n) = s.strip_prefix("buy ") {
+            return n.trim().parse().ok().map(Self::Buy);
+        }
+        if s == "man" || s.starts_with("man ") {
+            return Some(Self::Man(s["man".len()..].trim().to_owned()));
+        }
         #[allow(unreachable_patterns)]
-        Some(match s.to_ascii_lowercase().as_str() {
+        Some(match s.as_str() {
             "r" | "restart" => Self::Restart,
             "c" | "show" | "code" | "show code" => Self::ShowCode,
             "f" | "r" | "finish" | "finished" | "release" => Self::Send,
             "e" | "exit" => Self::Exit,
             "h" | "help" => Self::Help,
+            "i" | "inbox" | "mail" => Self::ShowInbox,
+            "tutorial" => Self::Tutorial,
+            "tutorial stop" | "tutorial off" => Self::TutorialStop,
+            "u" | "undo" => Self::Undo,
+            "bin" | "trash" | "recycle" => Self::ShowBin,
+            "shop" | "store" => Self::Shop,
+            "sort" => Self::Sort,
             _ => return None,
         })
     }
@@ -197,12 +307,16 @@ impl TerminalCommand {
         mut term_cmds: EventReader<Self>,
         mut levels: ResMut<Levels>,
         mut new_level: EventWriter<NewLevel>,
+        mut job_result: EventWriter<JobResult>,
         mut scanner_event: EventWriter<ScannerSoundEvent>,
         mut subs: ResMut<Submitted>,
         mut term_write: EventWriter<TermWrite>,
         mut total_pts: ResMut<TotalPoints>,
         timer: Res<LevelTimer>,
+        bin: Res<RecycleBin>,
+        score_config: Res<ScoreConfig>,
         locs: Query<Entity, With<LoCEntity>>,
+        planks: Query<(Entity, &LoCBlock)>,
     ) {
         let submit = term_cmds.iter().any(|c| *c == Self::Send);
         term_cmds.clear();
@@ -230,7 +344,18 @@ impl TerminalCommand {
         // calculate time bonus
         let time_score = (timer.time_left() / 10) as f64;
         let code_score = crate::score::score(&cor, &sub);
-        let score = time_score * code_score as f64;
+        // required lines that were deleted and never restored
+        let binned = bin.required(&cor, planks.iter());
+        let penalty = (1.0 - score_config.binned_line_penalty * binned as f64).max(0.0);
+        let score = time_score * code_score as f64 * penalty;
+        let penalty_note = match binned {
+            0 => String::new(),
+            n => format!(
+                "\n{}{n} required lines deleted{}",
+                ansi::YELLOW,
+                ansi::RESET
+            ),
+        };
 
         // calculate the possible total score of this level
         let possible_total_score = timer.duration().as_millis() as f64 / 10.0;
@@ -239,13 +364,21 @@ impl TerminalCommand {
         total_pts.sum += score;
         total_pts.total += score;
 
-        if (score / possible_total_score) > 0.45 {
+        let passed = (score / possible_total_score) > 0.45;
+        job_result.send(JobResult {
+            level: levels.current,
+            passed,
+        });
+
+        if passed {
             term_write.send(TermWrite {
                 s: format!(
-                    "\ntime: {}\naccuracy: {:.2}%\ntotal: {}\nPASS. Loading next job...\n>>",
+                    "\ntime: {}\naccuracy: {:.2}%{penalty_note}\ntotal: {}\n{}PASS.{} Loading next job...\n>>",
                     time_score as u64,
                     code_score * 100.0,
-                    score as u64
+                    score as u64,
+                    ansi::GREEN,
+                    ansi::RESET,
                 ),
             });
 
@@ -263,10 +396,12 @@ impl TerminalCommand {
             // FIXME: copied over from reset()
             term_write.send(TermWrite {
                 s: format!(
-                    "\ntime: {}\naccuracy: {:.2}%\ntotal: {}\nFAIL. Resetting playfield...\n>>",
+                    "\ntime: {}\naccuracy: {:.2}%{penalty_note}\ntotal: {}\n{}FAIL.{} Resetting playfield...\n>>",
                     time_score as u64,
                     code_score * 100.0,
-                    score as u64
+                    score as u64,
+                    ansi::RED,
+                    ansi::RESET,
                 ),
             });
 
@@ -283,7 +418,23 @@ impl TerminalCommand {
 }
 
 pub struct TermWrite {
-    s: String,
+    pub s: String,
+}
+
+/// Colours every line of a level's diff by its prefix
+fn colorize_diff(code: &str) -> String {
+    code.lines()
+        .map(|ln| {
+            let color = match Diff::from_line(ln) {
+                Diff::Pos => ansi::GREEN,
+                Diff::Neg => ansi::RED,
+                Diff::Rem => ansi::GREY,
+                Diff::Eq => ansi::RESET,
+            };
+            format!("{color}{ln}{}", ansi::RESET)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 fn locblk_to_loc(blk: LoCBlock) -> LineOfCode {
@@ -296,5 +447,55 @@ fn locblk_to_loc(blk: LoCBlock) -> LineOfCode {
         diff,
         color,
         code: blk.line_of_code,
+        typo: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands() {
+        use TerminalCommand::*;
+        for (s, cmd) in [
+            ("r", Restart),
+            ("RESTART", Restart),
+            ("show code", ShowCode),
+            ("f", Send),
+            ("help", Help),
+            ("tutorial off", TutorialStop),
+            ("bin", ShowBin),
+            ("store", Shop),
+            ("sort", Sort),
+        ] {
+            assert_eq!(TerminalCommand::from_str(s), Some(cmd), "{s}");
+        }
+    }
+
+    #[test]
+    fn commands_with_arguments() {
+        use TerminalCommand::*;
+        assert_eq!(TerminalCommand::from_str("read 2"), Some(ReadMail(2)));
+        assert_eq!(TerminalCommand::from_str("buy  3 "), Some(Buy(3)));
+        assert_eq!(TerminalCommand::from_str("man"), Some(Man(String::new())));
+        assert_eq!(
+            TerminalCommand::from_str("man Shop"),
+            Some(Man("shop".to_owned()))
+        );
+    }
+
+    #[test]
+    fn malformed_arguments() {
+        for s in ["read", "read two", "buy", "buy -1", "buy 1 2"] {
+            assert_eq!(TerminalCommand::from_str(s), None, "{s}");
+        }
+    }
+
+    #[test]
+    fn unknown_commands() {
+        for s in ["", "restart now", "manual", "rm -rf /"] {
+            assert_eq!(TerminalCommand::from_str(s), None, "{s}");
+        }
     }
 }
@@ -0,0 +1,140 @@
+use crate::prelude::*;
+
+// handy escapes for building coloured strings
+// e.g. format!("{GREEN}PASS{RESET}")
+pub const RESET: &str = "\x1b[0m";
+pub const RED: &str = "\x1b[31m";
+pub const GREEN: &str = "\x1b[32m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const GREY: &str = "\x1b[90m";
+
+pub const DEFAULT_COLOR: Color = Color::WHITE;
+
+fn sgr_color(code: u8) -> Option<Color> {
+    Some(match code {
+        0 | 39 => DEFAULT_COLOR,
+        30 => Color::BLACK,
+        31 => Color::rgb(0.8, 0.2, 0.2),
+        32 => Color::rgb(0.2, 0.8, 0.2),
+        33 => Color::rgb(0.8, 0.8, 0.2),
+        34 => Color::rgb(0.2, 0.2, 0.8),
+        35 => Color::rgb(0.8, 0.2, 0.8),
+        36 => Color::rgb(0.2, 0.8, 0.8),
+        37 => Color::rgb(0.8, 0.8, 0.8),
+        90 => Color::GRAY,
+        91 => Color::RED,
+        92 => Color::GREEN,
+        93 => Color::YELLOW,
+        94 => Color::BLUE,
+        95 => Color::FUCHSIA,
+        96 => Color::CYAN,
+        97 => Color::WHITE,
+        _ => return None,
+    })
+}
+
+/// Strips SGR escapes (`ESC [ n;n m`) out of `s`.
+/// Returns the plain text, and a colour for every char in it except linebreaks.
+/// `pen` is the colour in effect before `s` and is left at the colour in effect after it.
+/// Unsupported SGR parameters are ignored, as are any other escape sequences.
+pub fn parse(s: &str, pen: &mut Color) -> (String, Vec<Color>) {
+    let mut text = String::with_capacity(s.len());
+    let mut colors = Vec::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            text.push(ch);
+            if ch != '\n' {
+                colors.push(*pen);
+            }
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut end = None;
+        for ch in chars.by_ref() {
+            if ch.is_ascii_digit() || ch == ';' {
+                params.push(ch);
+            } else {
+                end = Some(ch);
+                break;
+            }
+        }
+        if end != Some('m') {
+            continue;
+        }
+        if params.is_empty() {
+            *pen = DEFAULT_COLOR;
+        }
+        for code in params.split(';').filter_map(|p| p.parse::<u8>().ok()) {
+            if let Some(color) = sgr_color(code) {
+                *pen = color;
+            }
+        }
+    }
+    (text, colors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_from_default(s: &str) -> (String, Vec<Color>, Color) {
+        let mut pen = DEFAULT_COLOR;
+        let (text, colors) = parse(s, &mut pen);
+        (text, colors, pen)
+    }
+
+    #[test]
+    fn plain_text_uses_the_pen() {
+        let mut pen = Color::BLUE;
+        let (text, colors) = parse("ab\nc", &mut pen);
+        assert_eq!(text, "ab\nc");
+        assert_eq!(colors, [Color::BLUE; 3]);
+        assert_eq!(pen, Color::BLUE);
+    }
+
+    #[test]
+    fn sgr_colours_what_follows() {
+        let (text, colors, pen) = parse_from_default(&format!("a{RED}b"));
+        assert_eq!(text, "ab");
+        assert_eq!(colors, [DEFAULT_COLOR, sgr_color(31).unwrap()]);
+        assert_eq!(pen, sgr_color(31).unwrap());
+    }
+
+    #[test]
+    fn sgr_resets() {
+        for reset in [RESET, "\x1b[m", "\x1b[39m"] {
+            let (text, colors, pen) = parse_from_default(&format!("{GREEN}a{reset}b"));
+            assert_eq!(text, "ab");
+            assert_eq!(colors, [sgr_color(32).unwrap(), DEFAULT_COLOR]);
+            assert_eq!(pen, DEFAULT_COLOR);
+        }
+    }
+
+    #[test]
+    fn unsupported_parameters_are_skipped() {
+        let (text, colors, _) = parse_from_default("\x1b[1;33;4ma");
+        assert_eq!(text, "a");
+        assert_eq!(colors, [sgr_color(33).unwrap()]);
+    }
+
+    #[test]
+    fn unterminated_escape_is_dropped() {
+        let (text, colors, pen) = parse_from_default("ab\x1b[31");
+        assert_eq!(text, "ab");
+        assert_eq!(colors.len(), 2);
+        assert_eq!(pen, DEFAULT_COLOR);
+    }
+
+    #[test]
+    fn other_escapes_are_ignored() {
+        // not SGR, so no colour change, just the escape itself goes
+        let (text, _, pen) = parse_from_default("\x1b[2Ja\x1bXb");
+        assert_eq!(text, "aXb");
+        assert_eq!(pen, DEFAULT_COLOR);
+    }
+}
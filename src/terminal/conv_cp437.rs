@@ -20,29 +20,83 @@ macro_rules! impl_conv {
     }
 }
 
+/// Glyph drawn for anything the atlas doesn't have (`■`)
+pub const REPLACEMENT_INDEX: usize = 0xfe;
+
+/// Like `index_of`, but never fails.
+/// Tabs become a space, everything else unknown becomes `REPLACEMENT_INDEX`
+pub fn index_of_or_replacement(ch: char) -> usize {
+    match ch {
+        '\t' => 0x20,
+        ch => index_of(ch).unwrap_or(REPLACEMENT_INDEX),
+    }
+}
+
+// the full code page 437 table, 0x00 is left out as it's blank
+// 0x01..=0x1f and 0x7f are the graphical glyphs, not the ascii control chars
 impl_conv!(
-    0x20 = ' ' 0x21 = '!' 0x22 = '"' 0x23 = '#'
-    0x24 = '$' 0x25 = '%' 0x26 = '&' 0x27 = '\''
-    0x28 = '(' 0x29 = ')' 0x2a = '*' 0x2b = '+'
-    0x2c = ',' 0x2d = '-' 0x2e = '.' 0x2f = '/'
-    0x30 = '0' 0x31 = '1' 0x32 = '2' 0x33 = '3'
-    0x34 = '4' 0x35 = '5' 0x36 = '6' 0x37 = '7'
-    0x38 = '8' 0x39 = '9' 0x3a = ':' 0x3b = ';'
-    0x3c = '<' 0x3d = '=' 0x3e = '>' 0x3f = '?'
-    0x40 = '@' 0x41 = 'A' 0x42 = 'B' 0x43 = 'C'
-    0x44 = 'D' 0x45 = 'E' 0x46 = 'F' 0x47 = 'G'
-    0x48 = 'H' 0x49 = 'I' 0x4a = 'J' 0x4b = 'K'
-    0x4c = 'L' 0x4d = 'M' 0x4e = 'N' 0x4f = 'O'
-    0x50 = 'P' 0x51 = 'Q' 0x52 = 'R' 0x53 = 'S'
-    0x54 = 'T' 0x55 = 'U' 0x56 = 'V' 0x57 = 'W'
-    0x58 = 'X' 0x59 = 'Y' 0x5a = 'Z' 0x5b = '['
-    0x5c = '\\'0x5d = ']' 0x5e = '^' 0x5f = '_'
-    0x60 = '`' 0x61 = 'a' 0x62 = 'b' 0x63 = 'c'
-    0x64 = 'd' 0x65 = 'e' 0x66 = 'f' 0x67 = 'g'
-    0x68 = 'h' 0x69 = 'i' 0x6a = 'j' 0x6b = 'k'
-    0x6c = 'l' 0x6d = 'm' 0x6e = 'n' 0x6f = 'o'
-    0x70 = 'p' 0x71 = 'q' 0x72 = 'r' 0x73 = 's'
-    0x74 = 't' 0x75 = 'u' 0x76 = 'v' 0x77 = 'w'
-    0x78 = 'x' 0x79 = 'y' 0x7a = 'z' 0x7b = '{'
-    0x7c = '|' 0x7d = '}' 0x7e = '~'
+    0x01 = '☺' 0x02 = '☻' 0x03 = '♥' 0x04 = '♦'
+    0x05 = '♣' 0x06 = '♠' 0x07 = '•' 0x08 = '◘'
+    0x09 = '○' 0x0a = '◙' 0x0b = '♂' 0x0c = '♀'
+    0x0d = '♪' 0x0e = '♫' 0x0f = '☼' 0x10 = '►'
+    0x11 = '◄' 0x12 = '↕' 0x13 = '‼' 0x14 = '¶'
+    0x15 = '§' 0x16 = '▬' 0x17 = '↨' 0x18 = '↑'
+    0x19 = '↓' 0x1a = '→' 0x1b = '←' 0x1c = '∟'
+    0x1d = '↔' 0x1e = '▲' 0x1f = '▼' 0x20 = ' '
+    0x21 = '!' 0x22 = '"' 0x23 = '#' 0x24 = '$'
+    0x25 = '%' 0x26 = '&' 0x27 = '\'' 0x28 = '('
+    0x29 = ')' 0x2a = '*' 0x2b = '+' 0x2c = ','
+    0x2d = '-' 0x2e = '.' 0x2f = '/' 0x30 = '0'
+    0x31 = '1' 0x32 = '2' 0x33 = '3' 0x34 = '4'
+    0x35 = '5' 0x36 = '6' 0x37 = '7' 0x38 = '8'
+    0x39 = '9' 0x3a = ':' 0x3b = ';' 0x3c = '<'
+    0x3d = '=' 0x3e = '>' 0x3f = '?' 0x40 = '@'
+    0x41 = 'A' 0x42 = 'B' 0x43 = 'C' 0x44 = 'D'
+    0x45 = 'E' 0x46 = 'F' 0x47 = 'G' 0x48 = 'H'
+    0x49 = 'I' 0x4a = 'J' 0x4b = 'K' 0x4c = 'L'
+    0x4d = 'M' 0x4e = 'N' 0x4f = 'O' 0x50 = 'P'
+    0x51 = 'Q' 0x52 = 'R' 0x53 = 'S' 0x54 = 'T'
+    0x55 = 'U' 0x56 = 'V' 0x57 = 'W' 0x58 = 'X'
+    0x59 = 'Y' 0x5a = 'Z' 0x5b = '[' 0x5c = '\\'
+    0x5d = ']' 0x5e = '^' 0x5f = '_' 0x60 = '`'
+    0x61 = 'a' 0x62 = 'b' 0x63 = 'c' 0x64 = 'd'
+    0x65 = 'e' 0x66 = 'f' 0x67 = 'g' 0x68 = 'h'
+    0x69 = 'i' 0x6a = 'j' 0x6b = 'k' 0x6c = 'l'
+    0x6d = 'm' 0x6e = 'n' 0x6f = 'o' 0x70 = 'p'
+    0x71 = 'q' 0x72 = 'r' 0x73 = 's' 0x74 = 't'
+    0x75 = 'u' 0x76 = 'v' 0x77 = 'w' 0x78 = 'x'
+    0x79 = 'y' 0x7a = 'z' 0x7b = '{' 0x7c = '|'
+    0x7d = '}' 0x7e = '~' 0x7f = '⌂' 0x80 = 'Ç'
+    0x81 = 'ü' 0x82 = 'é' 0x83 = 'â' 0x84 = 'ä'
+    0x85 = 'à' 0x86 = 'å' 0x87 = 'ç' 0x88 = 'ê'
+    0x89 = 'ë' 0x8a = 'è' 0x8b = 'ï' 0x8c = 'î'
+    0x8d = 'ì' 0x8e = 'Ä' 0x8f = 'Å' 0x90 = 'É'
+    0x91 = 'æ' 0x92 = 'Æ' 0x93 = 'ô' 0x94 = 'ö'
+    0x95 = 'ò' 0x96 = 'û' 0x97 = 'ù' 0x98 = 'ÿ'
+    0x99 = 'Ö' 0x9a = 'Ü' 0x9b = '¢' 0x9c = '£'
+    0x9d = '¥' 0x9e = '₧' 0x9f = 'ƒ' 0xa0 = 'á'
+    0xa1 = 'í' 0xa2 = 'ó' 0xa3 = 'ú' 0xa4 = 'ñ'
+    0xa5 = 'Ñ' 0xa6 = 'ª' 0xa7 = 'º' 0xa8 = '¿'
+    0xa9 = '⌐' 0xaa = '¬' 0xab = '½' 0xac = '¼'
+    0xad = '¡' 0xae = '«' 0xaf = '»' 0xb0 = '░'
+    0xb1 = '▒' 0xb2 = '▓' 0xb3 = '│' 0xb4 = '┤'
+    0xb5 = '╡' 0xb6 = '╢' 0xb7 = '╖' 0xb8 = '╕'
+    0xb9 = '╣' 0xba = '║' 0xbb = '╗' 0xbc = '╝'
+    0xbd = '╜' 0xbe = '╛' 0xbf = '┐' 0xc0 = '└'
+    0xc1 = '┴' 0xc2 = '┬' 0xc3 = '├' 0xc4 = '─'
+    0xc5 = '┼' 0xc6 = '╞' 0xc7 = '╟' 0xc8 = '╚'
+    0xc9 = '╔' 0xca = '╩' 0xcb = '╦' 0xcc = '╠'
+    0xcd = '═' 0xce = '╬' 0xcf = '╧' 0xd0 = '╨'
+    0xd1 = '╤' 0xd2 = '╥' 0xd3 = '╙' 0xd4 = '╘'
+    0xd5 = '╒' 0xd6 = '╓' 0xd7 = '╫' 0xd8 = '╪'
+    0xd9 = '┘' 0xda = '┌' 0xdb = '█' 0xdc = '▄'
+    0xdd = '▌' 0xde = '▐' 0xdf = '▀' 0xe0 = 'α'
+    0xe1 = 'ß' 0xe2 = 'Γ' 0xe3 = 'π' 0xe4 = 'Σ'
+    0xe5 = 'σ' 0xe6 = 'µ' 0xe7 = 'τ' 0xe8 = 'Φ'
+    0xe9 = 'Θ' 0xea = 'Ω' 0xeb = 'δ' 0xec = '∞'
+    0xed = 'φ' 0xee = 'ε' 0xef = '∩' 0xf0 = '≡'
+    0xf1 = '±' 0xf2 = '≥' 0xf3 = '≤' 0xf4 = '⌠'
+    0xf5 = '⌡' 0xf6 = '÷' 0xf7 = '≈' 0xf8 = '°'
+    0xf9 = '∙' 0xfa = '·' 0xfb = '√' 0xfc = 'ⁿ'
+    0xfd = '²' 0xfe = '■' 0xff = '\u{a0}'
 );
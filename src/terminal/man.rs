@@ -0,0 +1,40 @@
+const MAN: &str = include_str!("../../assets/code/man.txt");
+const PAGE_SEP: &str = "NEXT_PAGE\n";
+
+struct Page {
+    topics: Vec<&'static str>,
+    body: &'static str,
+}
+
+/// first line is `topics:` followed by every name the page can be looked up by
+/// everything after it is the page itself
+fn pages() -> impl Iterator<Item = Page> {
+    MAN.split(PAGE_SEP).map(|page| {
+        let (head, body) = page.split_once('\n').unwrap_or((page, ""));
+        let topics = head
+            .strip_prefix("topics:")
+            .unwrap_or_else(|| panic!("man page is missing `topics:`: {head}"))
+            .split_whitespace()
+            .collect();
+        Page {
+            topics,
+            body: body.trim_end(),
+        }
+    })
+}
+
+/// what the `man` command prints, an empty topic lists all of them
+/// controls are written as `{Action}` in the pages, for `InputBindings::fill` to swap in
+pub fn lookup(topic: &str) -> String {
+    if topic.is_empty() {
+        let topics = pages()
+            .map(|page| page.topics[0])
+            .collect::<Vec<_>>()
+            .join(" | ");
+        return format!("topics: {topics}\nuse man <topic> to read one");
+    }
+    match pages().find(|page| page.topics.contains(&topic)) {
+        Some(page) => page.body.to_owned(),
+        None => format!("no manual entry for {topic}, use man to list topics"),
+    }
+}
@@ -0,0 +1,95 @@
+use super::*;
+use crate::office::OfficeAssets;
+
+pub const PREVIEW_NODE: &str = "render_target_preview";
+/// on the other side of the canvas from the terminals
+const PREVIEW_OFFSET: Vec3 = Vec3::from_array([-4000., 0., 0.]);
+
+/// Monitor showing the last scan, in the order it will be submitted
+#[derive(Component)]
+pub struct ScanPreview;
+
+impl ScanPreview {
+    fn text(scanned: Option<&[LoCBlock]>) -> String {
+        let lines = match scanned {
+            Some(lines) => lines,
+            None => return "nothing scanned yet".to_owned(),
+        };
+        let mut out = format!("last scan, {} lines:", lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            let color = match line.loc_type {
+                LocType::Green => ansi::GREEN,
+                LocType::Red => ansi::RED,
+                LocType::Neutral => ansi::RESET,
+            };
+            out.push_str(&format!(
+                "\n{}{:>2}.{} {color}{}{}",
+                ansi::YELLOW,
+                i + 1,
+                ansi::RESET,
+                line.line_of_code,
+                ansi::RESET
+            ));
+        }
+        out
+    }
+
+    /// Drawn onto the `render_target_preview` node, if the office has one
+    pub fn spawn(
+        mut commands: Commands,
+        font: Res<FontAtlas>,
+        office: Res<OfficeAssets>,
+        mut images: ResMut<Assets<Image>>,
+        mut materials: ResMut<Assets<StandardMaterial>>,
+    ) {
+        let builder = match office.assets.get(PREVIEW_NODE) {
+            Some(b) => b,
+            None => {
+                warn!("no {PREVIEW_NODE} in the office, scans won't be previewed");
+                return;
+            }
+        };
+        let target = TerminalScreenTarget::new(&mut images);
+        TextSprite::new(Self::text(None), font.atlas.clone(), 1.0)
+            .with_layout(TextLayout {
+                max_cols: Some(TERM_COLS),
+                ..default()
+            })
+            .spawn(&mut commands, |mut parent| {
+                parent.insert(ScanPreview);
+                parent.insert(Transform::from_translation(
+                    Vec3::new(
+                        (ATLAS_CHAR_W - TERM_W) / 2.0,
+                        (TERM_H - ATLAS_CHAR_H) / 2.,
+                        0.,
+                    ) + PREVIEW_OFFSET,
+                ));
+            });
+        target.set_up_2d(&mut commands, PREVIEW_OFFSET);
+
+        commands.spawn_bundle(MaterialMeshBundle {
+            mesh: builder.collider_mesh.clone().unwrap(),
+            material: materials.add(StandardMaterial {
+                base_color_texture: Some(target.image.clone()),
+                reflectance: 0.02,
+                ..Default::default()
+            }),
+            transform: builder.trans,
+            ..Default::default()
+        });
+    }
+
+    pub fn update(subs: Res<Submitted>, mut q_preview: Query<&mut TextSprite, With<ScanPreview>>) {
+        if !subs.is_changed() {
+            return;
+        }
+        for mut text_sprite in q_preview.iter_mut() {
+            *text_sprite = TextSprite::new(
+                Self::text(subs.last.as_deref()),
+                text_sprite.atlas.clone(),
+                text_sprite.scale,
+            )
+            .with_layout(text_sprite.layout);
+        }
+    }
+}
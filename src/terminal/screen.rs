@@ -7,13 +7,14 @@ use bevy::render::{
     },
 };
 
+/// The image a single terminal's text is rendered to
+#[derive(Component, Clone)]
 pub struct TerminalScreenTarget {
     pub image: Handle<Image>,
 }
 
-impl FromWorld for TerminalScreenTarget {
-    fn from_world(world: &mut World) -> Self {
-        let mut images = world.resource_mut::<Assets<Image>>();
+impl TerminalScreenTarget {
+    pub fn new(images: &mut Assets<Image>) -> Self {
         // render_target in OfficeAssets
         let size = Extent3d {
             width: 1280,
@@ -49,16 +50,19 @@ impl FromWorld for TerminalScreenTarget {
 pub struct TerminalCamera;
 
 impl TerminalScreenTarget {
-    pub fn set_up_2d(mut commands: Commands, target: Res<TerminalScreenTarget>) {
-        commands
-            .spawn_bundle(Camera2dBundle {
-                camera: Camera {
-                    target: RenderTarget::Image(target.image.clone()),
-                    priority: -1, // render first!
-                    ..Default::default()
-                },
+    /// `offset` is where this terminal's text sits on the 2D canvas
+    pub fn set_up_2d(&self, commands: &mut Commands, offset: Vec3) {
+        let mut camera = Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(self.image.clone()),
+                priority: -1, // render first!
                 ..Default::default()
-            })
+            },
+            ..Default::default()
+        };
+        camera.transform.translation += offset;
+        commands
+            .spawn_bundle(camera)
             .insert(TerminalCamera)
             .insert(UiCameraConfig { show_ui: false });
     }
@@ -1,47 +1,79 @@
 use super::*;
-use crate::office::OfficeAssets;
+use crate::office::{OfficeAssetKind, OfficeAssets};
+
+/// Every terminal's text lives on the same 2D canvas as everything else
+/// so space them out far enough to never show up on each other's screens
+pub const TERM_SPRITE_SPACING: Vec3 = Vec3::from_array([4000., 0., 0.]);
 
 impl TerminalInput {
+    /// Spawns a terminal for every `interactable_terminal*` node,
+    /// drawn onto the matching `render_target*` node
+    /// e.g. `interactable_terminal_docs` goes with `render_target_docs`
     pub fn spawn(
         mut commands: Commands,
         font: Res<FontAtlas>,
-        target: Res<TerminalScreenTarget>,
         office: Res<OfficeAssets>,
+        mut images: ResMut<Assets<Image>>,
         mut materials: ResMut<Assets<StandardMaterial>>,
     ) {
-        let mut prompt = TextSprite::new(PROPMPT.to_string(), font.atlas.clone(), 1.0);
-        let prompt_len = prompt.len();
-        prompt.spawn(
-            &mut commands,
-            |_| {},
-            |mut parent| {
+        let mut names = office
+            .assets
+            .iter()
+            .filter(|(_, builder)| matches!(builder.kind, OfficeAssetKind::Interactable))
+            .map(|(name, _)| *name)
+            .filter(|name| name.starts_with(TERMINAL_PREFIX))
+            .collect::<Vec<_>>();
+        // hashmap order isn't stable, keep the canvas layout the same between runs
+        names.sort_unstable();
+
+        for (i, name) in names.into_iter().enumerate() {
+            let suffix = name.strip_prefix(TERMINAL_PREFIX).unwrap();
+            let screen_name = format!("render_target{suffix}");
+            let builder = utils::unwrap_or_continue!(
+                office.assets.get(screen_name.as_str());
+                else warn!("no {screen_name} for {name}, skipping")
+            );
+            let offset = TERM_SPRITE_SPACING * i as f32;
+            let target = TerminalScreenTarget::new(&mut images);
+
+            let prompt = TextSprite::new(PROPMPT.to_string(), font.atlas.clone(), 1.0).with_layout(
+                TextLayout {
+                    max_cols: Some(TERM_COLS),
+                    ..default()
+                },
+            );
+            let prompt_len = prompt.len();
+            prompt.spawn(&mut commands, |mut parent| {
                 parent.insert(TerminalInput {
                     user_inp_start: prompt_len,
+                    name: name.to_string(),
                 });
-                parent.insert(Transform::from_xyz(
-                    (ATLAS_CHAR_W - TERM_W) / 2.0,
-                    (TERM_H - ATLAS_CHAR_H) / 2.,
-                    0.,
+                parent.insert(Transform::from_translation(
+                    Vec3::new(
+                        (ATLAS_CHAR_W - TERM_W) / 2.0,
+                        (TERM_H - ATLAS_CHAR_H) / 2.,
+                        0.,
+                    ) + offset,
                 ));
-            },
-        );
-
-        // spawning the terminal render stuff
-        let target_material_handle = materials.add(StandardMaterial {
-            base_color_texture: Some(target.image.clone()),
-            reflectance: 0.02,
-            unlit: false,
-            ..Default::default()
-        });
+                parent.insert(target.clone());
+            });
+            target.set_up_2d(&mut commands, offset);
 
-        let builder = office.assets.get("render_target").unwrap();
+            // spawning the terminal render stuff
+            let target_material_handle = materials.add(StandardMaterial {
+                base_color_texture: Some(target.image.clone()),
+                reflectance: 0.02,
+                unlit: false,
+                ..Default::default()
+            });
 
-        // The cube that will be rendered to the texture.
-        commands.spawn_bundle(MaterialMeshBundle {
-            mesh: builder.collider_mesh.clone().unwrap(),
-            material: target_material_handle,
-            transform: builder.trans,
-            ..Default::default()
-        });
+            // The cube that will be rendered to the texture.
+            commands.spawn_bundle(MaterialMeshBundle {
+                mesh: builder.collider_mesh.clone().unwrap(),
+                material: target_material_handle,
+                transform: builder.trans,
+                ..Default::default()
+            });
+        }
     }
 }
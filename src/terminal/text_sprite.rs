@@ -1,37 +1,45 @@
 use crate::prelude::*;
 use bevy::ecs::system::EntityCommands;
+use bevy::render::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::sprite::Mesh2dHandle;
 use bevy_asset_loader::prelude::AssetCollection;
 
-use super::conv_cp437;
+use super::{ansi, conv_cp437};
 
+/// `mono-cp437.png` is the whole CP437 table in code page order,
+/// 16 glyphs to a row with no gaps between them
 #[derive(AssetCollection)]
 pub struct FontAtlas {
-    #[asset(texture_atlas(
-        tile_size_x = 25.5,
-        tile_size_y = 62.,
-        columns = 16,
-        rows = 16,
-        padding_y = 2.5,
-    ))]
-    #[asset(path = "fonts/VT323-Regular_1x.png")]
+    #[asset(texture_atlas(tile_size_x = 32., tile_size_y = 64., columns = 16, rows = 16))]
+    #[asset(path = "fonts/mono-cp437.png")]
     pub atlas: Handle<TextureAtlas>,
 }
 
-pub const ATLAS_CHAR_W: f32 = 26.;
+/// the size of one glyph in the atlas, must match the `texture_atlas` tile size above
+pub const ATLAS_CHAR_W: f32 = 32.;
 pub const ATLAS_CHAR_H: f32 = 64.;
 
 pub struct TextSpritePlugin;
 
 impl Plugin for TextSpritePlugin {
-    fn build(&self, _: &mut App) {}
+    fn build(&self, app: &mut App) {
+        app.add_system(TextSprite::attach_mesh)
+            .add_system(TextSprite::update_mesh);
+    }
 }
 
+/// `text` never contains escape codes, they are parsed out into `colors`
+/// which has one entry per char in `text` (linebreaks excluded)
+///
+/// The whole text is drawn as a single mesh, see `TextSpriteMesh`
 #[derive(Component, Debug, Clone)]
 pub struct TextSprite {
     pub text: String,
+    pub colors: Vec<Color>,
+    pub pen: Color,
     pub atlas: Handle<TextureAtlas>,
     pub scale: f32,
-    pub chars: Vec<Entity>,
+    pub layout: TextLayout,
 }
 
 #[derive(Bundle)]
@@ -43,45 +51,151 @@ pub struct TextSpriteBundle {
     pub trans: TransformBundle,
 }
 
-#[derive(Component, Debug, Clone)]
-pub struct TextSpriteChar;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wrap {
+    /// break at the last space that fits, or mid-word if there is none
+    Word,
+    /// break exactly at `max_cols`
+    Hard,
+}
 
-#[derive(Bundle)]
-pub struct TextSpriteCharBundle {
-    this: TextSpriteChar,
-    #[bundle]
-    sprite: SpriteSheetBundle,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Centre,
+    Right,
+}
+
+/// How the lines of a `TextSprite` are laid out into rows.
+/// Only affects where glyphs are drawn, `TextSprite::text` is left untouched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLayout {
+    /// `None` never wraps
+    pub max_cols: Option<usize>,
+    pub wrap: Wrap,
+    /// Relative to `max_cols`, or the longest row if there is none
+    pub align: Align,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            max_cols: None,
+            wrap: Wrap::Word,
+            align: Align::Left,
+        }
+    }
+}
+
+impl TextLayout {
+    /// Splits a single line (no linebreaks) into rows
+    fn wrap_line(&self, line: &str) -> Vec<Vec<char>> {
+        let mut rows = vec![Vec::new()];
+        for ch in line.chars() {
+            let row = rows.last_mut().unwrap();
+            if matches!(self.max_cols, Some(max) if max > 0 && row.len() >= max) {
+                // move the unfinished word down with us
+                let carry = match self.wrap {
+                    Wrap::Word => match row.iter().rposition(|c| *c == ' ') {
+                        Some(p) if p + 1 < row.len() => row.split_off(p + 1),
+                        _ => Vec::new(),
+                    },
+                    Wrap::Hard => Vec::new(),
+                };
+                rows.push(carry);
+            }
+            rows.last_mut().unwrap().push(ch);
+        }
+        rows
+    }
 }
 
-pub fn iter_row_col(s: &str) -> impl Iterator<Item = (usize, usize, char)> + '_ {
-    s.lines()
-        .enumerate()
-        .flat_map(|(ri, col)| col.chars().enumerate().map(move |(ci, ch)| (ri, ci, ch)))
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Glyph {
+    row: usize,
+    col: usize,
+    ch: char,
+    color: Color,
+}
+
+/// CPU side copy of the glyph quads of a `TextSprite`
+/// kept around so only glyphs that actually moved or changed get rebuilt
+#[derive(Component, Default)]
+pub struct TextSpriteMesh {
+    built: Vec<Glyph>,
+    positions: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    colors: Vec<[f32; 4]>,
 }
 
 impl TextSprite {
+    /// `text` may contain ANSI SGR escapes to colour it, see `ansi::parse`
     pub fn new(text: String, atlas: Handle<TextureAtlas>, scale: f32) -> Self {
+        let mut pen = ansi::DEFAULT_COLOR;
+        let (text, colors) = ansi::parse(&text, &mut pen);
         Self {
             text,
+            colors,
+            pen,
             atlas,
             scale,
-            chars: Vec::new(),
+            layout: TextLayout::default(),
         }
     }
-    /// Spawns the text stored in the TextSpriteBuilder
-    /// Takes closures to be applied to every entity spawned
+
+    pub fn with_layout(mut self, layout: TextLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    fn rows(&self) -> Vec<Vec<char>> {
+        self.text
+            .lines()
+            .flat_map(|ln| self.layout.wrap_line(ln))
+            .collect()
+    }
+
+    /// number of rows the text takes up once wrapped
+    pub fn row_count(&self) -> usize {
+        self.rows().len()
+    }
+
+    /// Where every char (linebreaks excluded) ends up, in order
+    fn glyphs(&self) -> Vec<Glyph> {
+        let rows = self.rows();
+        let width = self
+            .layout
+            .max_cols
+            .unwrap_or_else(|| rows.iter().map(Vec::len).max().unwrap_or(0));
+        let mut colors = self.colors.iter().copied();
+        let mut glyphs = Vec::with_capacity(self.colors.len());
+        for (row, chars) in rows.iter().enumerate() {
+            let free = width.saturating_sub(chars.len());
+            let indent = match self.layout.align {
+                Align::Left => 0,
+                Align::Centre => free / 2,
+                Align::Right => free,
+            };
+            for (col, &ch) in chars.iter().enumerate() {
+                glyphs.push(Glyph {
+                    row,
+                    col: col + indent,
+                    ch,
+                    color: colors.next().unwrap_or(ansi::DEFAULT_COLOR),
+                });
+            }
+        }
+        glyphs
+    }
+
+    /// Spawns the text stored in the TextSprite
+    /// Takes a closure to be applied to the spawned entity
     /// If further modifications are required
-    pub fn spawn<ChildModifier, ParentModifier>(
-        &mut self,
-        commands: &mut Commands,
-        child_modifier: ChildModifier,
-        parent_modifier: ParentModifier,
-    ) where
-        ChildModifier: FnMut(EntityCommands),
+    pub fn spawn<ParentModifier>(&self, commands: &mut Commands, parent_modifier: ParentModifier)
+    where
         ParentModifier: FnOnce(EntityCommands),
     {
         let mut parent = commands.spawn();
-        parent.add_children(|builder| self.spawn_chars(builder, child_modifier, 0));
         parent.insert_bundle(TextSpriteBundle {
             this: self.clone(),
             vis: VisibilityBundle::default(),
@@ -90,94 +204,358 @@ impl TextSprite {
         parent_modifier(parent)
     }
 
-    /// `offset` is the number of chars to skip before spawning new ones
-    pub fn spawn_chars<ChildModifier>(
-        &mut self,
-        builder: &mut ChildBuilder,
-        mut child_modifier: ChildModifier,
-        offset: usize,
-    ) where
-        ChildModifier: FnMut(EntityCommands),
-    {
-        let (w, h) = (self.scale * ATLAS_CHAR_W, self.scale * ATLAS_CHAR_H);
-        assert!(
-            self.len() >= offset,
-            "cannot offset by more than the text's length"
-        );
-        for (ri, ci, ch) in iter_row_col(&self.text).skip(offset) {
-            let child_commands = builder.spawn_bundle(TextSpriteCharBundle {
-                this: TextSpriteChar,
-                sprite: SpriteSheetBundle {
-                    sprite: TextureAtlasSprite::new(conv_cp437::index_of(ch).unwrap()),
-                    texture_atlas: self.atlas.clone(),
-                    transform: Transform {
-                        scale: Vec3::splat(self.scale),
-                        translation: Vec3::new(ci as f32 * w, ri as f32 * -h, 0.0),
-                        ..default()
-                    },
-                    ..default()
-                },
-            });
-            self.chars.push(child_commands.id());
-            child_modifier(child_commands);
-        }
-    }
-    /// length disregarding linebreaks
+    /// length in chars disregarding linebreaks
     pub fn len(&self) -> usize {
-        self.text.lines().map(|ln| ln.len()).sum()
+        self.text.lines().map(|ln| ln.chars().count()).sum()
     }
 
-    pub fn add_str<ChildModifier>(
-        &mut self,
-        s: &str,
-        commands: &mut Commands,
-        parent: Entity,
-        child_modifier: ChildModifier,
-    ) where
-        ChildModifier: FnMut(EntityCommands),
-    {
-        let offset = self.len();
-        self.text.push_str(s);
-        commands
-            .entity(parent)
-            .add_children(|builder| self.spawn_chars(builder, child_modifier, offset))
+    pub fn add_str(&mut self, s: &str) {
+        let (text, colors) = ansi::parse(s, &mut self.pen);
+        self.text.push_str(&text);
+        self.colors.extend(colors);
     }
 
-    pub fn add_multiline_str(&mut self, s: &str, commands: &mut Commands, parent: Entity) {
+    pub fn add_multiline_str(&mut self, s: &str) {
         let mut lines = s.lines().peekable();
         while let (Some(ln), last) = (lines.next(), lines.peek().is_none()) {
-            self.add_str(ln, commands, parent, |_| {});
+            self.add_str(ln);
             if !last {
                 self.push_newline()
             }
         }
     }
 
-    pub fn remove_top_lines(&mut self, commands: &mut Commands, parent: Entity, count: usize) {
-        for ch in self.chars.drain(..) {
-            commands.entity(ch).despawn();
-        }
-        let text = self
+    pub fn remove_top_lines(&mut self, count: usize) {
+        let removed = self
+            .text
+            .lines()
+            .take(count)
+            .map(|ln| ln.chars().count())
+            .sum::<usize>();
+        self.colors.drain(..removed.min(self.colors.len()));
+        self.text = self
             .text
             .lines()
             .skip(count)
             .collect::<Vec<&str>>()
             .join("\n");
-        self.text.clear();
-        // commands.entity(parent).add_children(|builder| self.spawn_chars(builder, |_| {}, 0));
-        self.add_multiline_str(&text, commands, parent);
+    }
+
+    /// Like `remove_top_lines(1)`, but a line that wraps only loses its first row
+    pub fn remove_top_row(&mut self) {
+        let first = self.text.lines().next().unwrap_or_default();
+        let rows = self.layout.wrap_line(first);
+        if rows.len() < 2 {
+            self.remove_top_lines(1);
+            return;
+        }
+        let removed = rows[0].len();
+        self.colors.drain(..removed.min(self.colors.len()));
+        self.text = self.text.chars().skip(removed).collect();
     }
 
     pub fn push_newline(&mut self) {
         self.text.push('\n');
     }
 
-    pub fn pop(&mut self, commands: &mut Commands) {
+    pub fn pop(&mut self) {
         if self.text.pop() != Some('\n') {
-            self.chars
-                .pop()
-                .into_iter()
-                .for_each(|entity| commands.entity(entity).despawn());
+            self.colors.pop();
+        }
+    }
+
+    fn attach_mesh(
+        mut commands: Commands,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut materials: ResMut<Assets<ColorMaterial>>,
+        atlases: Res<Assets<TextureAtlas>>,
+        q_text: Query<(Entity, &TextSprite), Without<TextSpriteMesh>>,
+    ) {
+        for (entity, text_sprite) in q_text.iter() {
+            let atlas = utils::unwrap_or_continue!(atlases.get(&text_sprite.atlas));
+            let mut text_mesh = TextSpriteMesh::default();
+            text_mesh.sync(text_sprite, atlas);
+            let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+            text_mesh.write(&mut mesh, 0);
+            commands
+                .entity(entity)
+                .insert(Mesh2dHandle(meshes.add(mesh)))
+                .insert(materials.add(ColorMaterial {
+                    color: Color::WHITE,
+                    texture: Some(atlas.texture.clone()),
+                }))
+                .insert(text_mesh);
+        }
+    }
+
+    fn update_mesh(
+        mut meshes: ResMut<Assets<Mesh>>,
+        atlases: Res<Assets<TextureAtlas>>,
+        mut q_text: Query<(&TextSprite, &mut TextSpriteMesh, &Mesh2dHandle), Changed<TextSprite>>,
+    ) {
+        for (text_sprite, mut text_mesh, mesh_handle) in q_text.iter_mut() {
+            let atlas = utils::unwrap_or_continue!(atlases.get(&text_sprite.atlas));
+            let from = utils::unwrap_or_continue!(text_mesh.sync(text_sprite, atlas));
+            let mesh = utils::unwrap_or_continue!(meshes.get_mut(&mesh_handle.0));
+            text_mesh.write(mesh, from);
+        }
+    }
+}
+
+impl TextSpriteMesh {
+    fn glyph_count(&self) -> usize {
+        self.positions.len() / 4
+    }
+
+    /// Brings the quads up to date with `text_sprite`
+    /// Everything before the first glyph that moved or changed is kept as is
+    /// Returns that first glyph, or `None` if there was nothing to do
+    fn sync(&mut self, text_sprite: &TextSprite, atlas: &TextureAtlas) -> Option<usize> {
+        let glyphs = text_sprite.glyphs();
+        if glyphs == self.built {
+            return None;
+        }
+        let keep = self
+            .built
+            .iter()
+            .zip(glyphs.iter())
+            .take_while(|(old, new)| old == new)
+            .count();
+        self.positions.truncate(keep * 4);
+        self.uvs.truncate(keep * 4);
+        self.colors.truncate(keep * 4);
+
+        let scale = text_sprite.scale;
+        let (w, h) = (scale * ATLAS_CHAR_W, scale * ATLAS_CHAR_H);
+        for glyph in &glyphs[keep..] {
+            // glyphs missing from the atlas become an empty quad
+            // so the quad count always matches the char count
+            let (min, max) = match atlas
+                .textures
+                .get(conv_cp437::index_of_or_replacement(glyph.ch))
+            {
+                Some(rect) => (rect.min, rect.max),
+                None => (Vec2::ZERO, Vec2::ZERO),
+            };
+            let center = Vec2::new(glyph.col as f32 * w, glyph.row as f32 * -h);
+            let half = (max - min) * scale * 0.5;
+            let (uv_min, uv_max) = (min / atlas.size, max / atlas.size);
+            let color = glyph.color.as_linear_rgba_f32();
+
+            self.positions.extend([
+                [center.x - half.x, center.y - half.y, 0.0],
+                [center.x + half.x, center.y - half.y, 0.0],
+                [center.x + half.x, center.y + half.y, 0.0],
+                [center.x - half.x, center.y + half.y, 0.0],
+            ]);
+            self.uvs.extend([
+                [uv_min.x, uv_max.y],
+                [uv_max.x, uv_max.y],
+                [uv_max.x, uv_min.y],
+                [uv_min.x, uv_min.y],
+            ]);
+            self.colors.extend([color; 4]);
+        }
+        self.built = glyphs;
+        Some(keep)
+    }
+
+    /// Copies the quads from glyph `from` onwards into `mesh`, the ones before it are left alone.
+    /// `ColorMaterial` multiplies its colour by `ATTRIBUTE_COLOR` (the mesh2d pipeline turns on
+    /// `VERTEX_COLORS` when a mesh has it), which is how each glyph gets its own colour
+    fn write(&self, mesh: &mut Mesh, from: usize) {
+        let v = from * 4;
+        let count = self.positions.len();
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(buf)) => splice(buf, &self.positions, v),
+            _ => mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, self.positions.clone()),
+        }
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_NORMAL) {
+            Some(VertexAttributeValues::Float32x3(buf)) => buf.resize(count, [0.0, 0.0, 1.0]),
+            _ => mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 0.0, 1.0]; count]),
+        }
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(buf)) => splice(buf, &self.uvs, v),
+            _ => mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, self.uvs.clone()),
+        }
+        match mesh.attribute_mut(Mesh::ATTRIBUTE_COLOR) {
+            Some(VertexAttributeValues::Float32x4(buf)) => splice(buf, &self.colors, v),
+            _ => mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, self.colors.clone()),
+        }
+
+        let indices = |glyphs: std::ops::Range<usize>| {
+            glyphs.flat_map(|g| {
+                let v = g as u32 * 4;
+                [v, v + 1, v + 2, v, v + 2, v + 3]
+            })
+        };
+        match mesh.indices_mut() {
+            Some(Indices::U32(buf)) => {
+                let kept = from.min(buf.len() / 6);
+                buf.truncate(kept * 6);
+                buf.extend(indices(kept..self.glyph_count()));
+            }
+            _ => mesh.set_indices(Some(Indices::U32(indices(0..self.glyph_count()).collect()))),
+        }
+    }
+}
+
+/// Makes `buf` the same as `values`, which it already matches up to `from`
+fn splice<T: Copy>(buf: &mut Vec<T>, values: &[T], from: usize) {
+    let from = from.min(buf.len()).min(values.len());
+    buf.truncate(from);
+    buf.extend_from_slice(&values[from..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(layout: TextLayout, line: &str) -> Vec<String> {
+        layout
+            .wrap_line(line)
+            .into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+
+    fn layout(max_cols: usize, wrap: Wrap) -> TextLayout {
+        TextLayout {
+            max_cols: Some(max_cols),
+            wrap,
+            ..default()
         }
     }
+
+    #[test]
+    fn word_wrap_carries_the_unfinished_word() {
+        assert_eq!(
+            rows(layout(8, Wrap::Word), "hello world"),
+            ["hello ", "world"]
+        );
+    }
+
+    #[test]
+    fn word_wrap_breaks_mid_word_without_a_space() {
+        assert_eq!(
+            rows(layout(4, Wrap::Word), "abcdefghij"),
+            ["abcd", "efgh", "ij"]
+        );
+    }
+
+    #[test]
+    fn hard_wrap_breaks_at_max_cols() {
+        assert_eq!(
+            rows(layout(8, Wrap::Hard), "hello world"),
+            ["hello wo", "rld"]
+        );
+    }
+
+    #[test]
+    fn exactly_max_cols_is_one_row() {
+        for wrap in [Wrap::Word, Wrap::Hard] {
+            assert_eq!(rows(layout(5, wrap), "abcde"), ["abcde"]);
+        }
+    }
+
+    #[test]
+    fn no_max_cols_never_wraps() {
+        let long = "a".repeat(500);
+        assert_eq!(rows(TextLayout::default(), &long), [long]);
+    }
+
+    /// the column every char of `text` is drawn at, row by row
+    fn columns(text: &str, align: Align) -> Vec<Vec<usize>> {
+        let text = TextSprite::new(text.to_owned(), default(), 1.0).with_layout(TextLayout {
+            max_cols: Some(6),
+            wrap: Wrap::Word,
+            align,
+        });
+        let glyphs = text.glyphs();
+        (0..text.row_count())
+            .map(|row| {
+                glyphs
+                    .iter()
+                    .filter(|g| g.row == row)
+                    .map(|g| g.col)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn alignment() {
+        assert_eq!(
+            columns("ab\nabcdef", Align::Left),
+            [vec![0, 1], vec![0, 1, 2, 3, 4, 5]]
+        );
+        assert_eq!(
+            columns("ab\nabcdef", Align::Centre),
+            [vec![2, 3], vec![0, 1, 2, 3, 4, 5]]
+        );
+        assert_eq!(
+            columns("ab\nabcdef", Align::Right),
+            [vec![4, 5], vec![0, 1, 2, 3, 4, 5]]
+        );
+    }
+
+    #[test]
+    fn alignment_without_max_cols_uses_the_longest_row() {
+        let text = TextSprite::new("abcd\nab".to_owned(), default(), 1.0).with_layout(TextLayout {
+            align: Align::Right,
+            ..default()
+        });
+        let cols = text.glyphs().iter().map(|g| g.col).collect::<Vec<_>>();
+        assert_eq!(cols, [0, 1, 2, 3, 2, 3]);
+    }
+
+    #[test]
+    fn remove_top_row_trims_a_wrapped_line() {
+        let mut text = TextSprite::new("abcdefghij".to_owned(), default(), 1.0)
+            .with_layout(layout(4, Wrap::Hard));
+        text.remove_top_row();
+        assert_eq!(text.text, "efghij");
+        assert_eq!(text.colors.len(), 6);
+        assert_eq!(text.row_count(), 2);
+    }
+
+    #[test]
+    fn incremental_write_matches_a_fresh_mesh() {
+        let atlas =
+            TextureAtlas::from_grid(default(), Vec2::new(ATLAS_CHAR_W, ATLAS_CHAR_H), 16, 16);
+        let mut text = TextSprite::new("hello\nworld".to_owned(), default(), 1.0);
+        let mut text_mesh = TextSpriteMesh::default();
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        let from = text_mesh.sync(&text, &atlas).unwrap();
+        text_mesh.write(&mut mesh, from);
+
+        text.pop();
+        text.add_str(&format!("{}!{} there", ansi::RED, ansi::RESET));
+        let from = text_mesh.sync(&text, &atlas).unwrap();
+        assert_eq!(from, 9);
+        text_mesh.write(&mut mesh, from);
+
+        let mut fresh = Mesh::new(PrimitiveTopology::TriangleList);
+        let mut fresh_mesh = TextSpriteMesh::default();
+        let from = fresh_mesh.sync(&text, &atlas).unwrap();
+        fresh_mesh.write(&mut fresh, from);
+        for attribute in [
+            Mesh::ATTRIBUTE_POSITION,
+            Mesh::ATTRIBUTE_NORMAL,
+            Mesh::ATTRIBUTE_UV_0,
+            Mesh::ATTRIBUTE_COLOR,
+        ] {
+            assert_eq!(
+                mesh.attribute(attribute.id).map(|a| a.get_bytes().to_vec()),
+                fresh
+                    .attribute(attribute.id)
+                    .map(|a| a.get_bytes().to_vec()),
+            );
+        }
+        assert_eq!(
+            mesh.indices().map(|i| i.iter().collect::<Vec<_>>()),
+            fresh.indices().map(|i| i.iter().collect::<Vec<_>>()),
+        );
+        assert_eq!(mesh.count_vertices(), text.len() * 4);
+    }
 }
@@ -1,17 +1,26 @@
 use crate::audio::events::CollisionSoundEvent;
-use crate::level::NewLevel;
+use crate::config::ScoreConfig;
+use crate::level::{LevelTimer, Levels, NewLevel};
 use crate::phys::group::collide::interactable_dynamic_body;
 use crate::{
-    code::{LoCBlock, LoCEntity, LocType},
+    code::{plank_collider, LineOfCode, LoCBlock, LoCEntity, LocType, PlankLabel, PlankSpawner},
     collider::{ColliderBundle, PhysicsBundle},
     interactable::{Interactable, InteractableType},
     office::SceneLocations,
+    player::interactions::OrderedChildren,
     prelude::*,
-    unwrap_or_continue,
+    recycle::RecycleBin,
+    shop::Upgrades,
+    terminal::{ansi, TermWrite},
+    unresult_or_continue, unwrap_or_continue,
     utils::EName,
 };
+use bevy::ecs::system::SystemParam;
 use bevy_asset_loader::prelude::AssetCollection;
-use bevy_rapier3d::prelude::{ActiveEvents, Collider, CollisionEvent, RigidBody, Sensor};
+use bevy_rapier3d::prelude::{
+    ActiveCollisionTypes, ActiveEvents, Collider, CollisionEvent, RigidBody, Sensor,
+};
+use std::time::Duration;
 
 #[derive(AssetCollection)]
 pub struct HammerModel {
@@ -25,6 +34,9 @@ pub enum SType {
     Painter,
     Deleter,
     Scanner,
+    Splitter,
+    Duplicator,
+    Linter,
 }
 
 pub struct SensorEvent {
@@ -32,6 +44,133 @@ pub struct SensorEvent {
     pub loc: Option<Vec<LoCBlock>>,
 }
 
+/// What a `sensor_*` node in the office does, attached when the sensor is spawned
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ToolKind {
+    Painter(LocType),
+    Deleter,
+    Scanner,
+    Splitter(SplitOff),
+    Duplicator,
+    Typewriter,
+    Linter,
+}
+
+/// Which planks a splitter takes off a bundle
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SplitOff {
+    All,
+    First,
+    Last,
+}
+
+impl ToolKind {
+    /// Anything after the tool's name is ignored
+    /// so `sensor_pp` and `sensor_pp_2` are both green painters
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("sensor_")?;
+        for (prefix, kind) in [
+            ("pp", Self::Painter(LocType::Green)),
+            ("nn", Self::Painter(LocType::Red)),
+            ("ee", Self::Painter(LocType::Neutral)),
+            ("deleter", Self::Deleter),
+            ("scandesk", Self::Scanner),
+            ("splitter", Self::Splitter(SplitOff::All)),
+            ("popfirst", Self::Splitter(SplitOff::First)),
+            ("poplast", Self::Splitter(SplitOff::Last)),
+            ("copier", Self::Duplicator),
+            ("duplicator", Self::Duplicator),
+            ("typewriter", Self::Typewriter),
+            ("linter", Self::Linter),
+        ] {
+            if name.starts_with(prefix) {
+                return Some(kind);
+            }
+        }
+        None
+    }
+}
+
+/// A line of code (or anything else) touched a tool's sensor
+pub struct ToolHit {
+    pub kind: ToolKind,
+    pub tool: Entity,
+    pub item: Entity,
+}
+
+/// Planks made by the duplicator, which won't copy them again
+/// so they can't loop back into it from its output
+#[derive(Component)]
+pub struct PlankCopy;
+
+/// How many planks the duplicator made this level
+#[derive(Default)]
+pub struct CopiesMade(pub usize);
+
+/// gap between planks laid out on a splitter's tray
+const TRAY_SPACING: f32 = 0.12;
+
+/// `planks` in the order their lines come in `code`, lines that aren't in it go last.
+/// Duplicate lines each claim the next matching line of the level
+pub fn level_order(
+    planks: &[Entity],
+    code: &[LineOfCode],
+    blocks: &Query<&LoCBlock>,
+) -> Vec<Entity> {
+    let mut claimed = vec![false; code.len()];
+    let mut keyed = Vec::with_capacity(planks.len());
+    for plank in planks.iter() {
+        let key = blocks.get(*plank).ok().and_then(|block| {
+            let i = code
+                .iter()
+                .enumerate()
+                .position(|(i, loc)| !claimed[i] && loc.code == block.line_of_code)?;
+            claimed[i] = true;
+            Some(i)
+        });
+        keyed.push((key.unwrap_or(usize::MAX), *plank));
+    }
+    keyed.sort_by_key(|(key, _)| *key);
+    keyed.into_iter().map(|(_, plank)| plank).collect()
+}
+
+/// Where stations put the things they make
+#[derive(SystemParam)]
+pub struct StationOutputs<'w, 's> {
+    locations: Res<'w, SceneLocations>,
+    names: Query<'w, 's, &'static EName>,
+    sensors: Query<'w, 's, &'static GlobalTransform, With<Sensor>>,
+}
+
+impl<'w, 's> StationOutputs<'w, 's> {
+    /// `point3d_<name>_<output>` for `sensor_<name>`, or just above the station without one
+    pub fn find(&self, tool: Entity, output: &str) -> Transform {
+        self.names
+            .get(tool)
+            .ok()
+            .and_then(|n| n.id.strip_prefix("sensor_"))
+            .and_then(|n| {
+                self.locations
+                    .locations
+                    .get(format!("point3d_{n}_{output}").as_str())
+                    .copied()
+            })
+            .unwrap_or_else(|| {
+                let above = self
+                    .sensors
+                    .get(tool)
+                    .map(|t| t.translation())
+                    .unwrap_or_default();
+                Transform::from_translation(above + Vec3::Y * 0.5)
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemLabel)]
+enum ToolSystemLabel {
+    Detect,
+}
+
 pub struct ToolsPlugin;
 
 impl ToolsPlugin {
@@ -66,211 +205,309 @@ impl ToolsPlugin {
             .insert(LoCEntity);
     }
 
-    // i know, this is pain
-    // bear with me
-    pub fn loc_sensor_interacts(
+    pub fn detect_tool_hits(
         mut events: EventReader<CollisionEvent>,
-        mut sensor_event: EventWriter<SensorEvent>,
+        mut hits: EventWriter<ToolHit>,
         mut sound_event: EventWriter<CollisionSoundEvent>,
+        sensors: Query<Option<&ToolKind>, With<Sensor>>,
+    ) {
+        for event in events.iter() {
+            if let CollisionEvent::Started(entity1, entity2, _) = event {
+                let (kind, tool, item) = match (sensors.get(*entity1), sensors.get(*entity2)) {
+                    (Ok(kind), _) => (kind, *entity1, *entity2),
+                    (_, Ok(kind)) => (kind, *entity2, *entity1),
+                    _ => {
+                        sound_event.send(CollisionSoundEvent);
+                        continue;
+                    }
+                };
+                // a sensor that isn't a tool, e.g. a typo in the gltf
+                let kind = unwrap_or_continue!(kind);
+                hits.send(ToolHit {
+                    kind: *kind,
+                    tool,
+                    item,
+                });
+            }
+        }
+    }
+
+    /// Paints a plank, or every plank of a bundle once the wide brush has been bought
+    pub fn paint(
+        mut hits: EventReader<ToolHit>,
+        mut sensor_event: EventWriter<SensorEvent>,
         mut materials: ResMut<Assets<StandardMaterial>>,
-        mut commands: Commands,
-        sensors: Query<&EName, With<Sensor>>,
+        upgrades: Res<Upgrades>,
+        bundles: Query<&OrderedChildren>,
         mut items: Query<
             (&mut LoCBlock, &Handle<StandardMaterial>),
             (Without<Sensor>, Without<EName>),
         >,
+    ) {
+        for hit in hits.iter() {
+            let loc_type = match hit.kind {
+                ToolKind::Painter(loc_type) => loc_type,
+                _ => continue,
+            };
+            let planks = match bundles.get(hit.item) {
+                Ok(planks) if upgrades.bundle_paint => planks.0.clone(),
+                _ => vec![hit.item],
+            };
+            let mut painted = false;
+            for plank in planks {
+                let (mut loc, mat_h) = unresult_or_continue!(items.get_mut(plank));
+                loc.loc_type = loc_type;
+                let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
+                real_asset.base_color = loc_type.color();
+                painted = true;
+            }
+            if painted {
+                sensor_event.send(SensorEvent {
+                    stype: SType::Painter,
+                    loc: None,
+                });
+            }
+        }
+    }
+
+    /// Deleted planks go to the recycle bin so they can be restored
+    pub fn delete(
+        mut commands: Commands,
+        mut hits: EventReader<ToolHit>,
+        mut sensor_event: EventWriter<SensorEvent>,
+        mut bin: ResMut<RecycleBin>,
         iteractable: Query<&Interactable, (Without<Sensor>, Without<EName>)>,
-        children: Query<&Children>,
+        blocks: Query<(&LoCBlock, &Collider)>,
+        bundles: Query<&OrderedChildren>,
     ) {
-        for event in events.iter() {
-            if let CollisionEvent::Started(entity1, entity2, _) = event {
-                let ename = match sensors.get(*entity1) {
-                    Ok(e) => e,
-                    Err(_) => match sensors.get(*entity2) {
-                        Ok(o) => o,
-                        Err(_) => {
-                            sound_event.send(CollisionSoundEvent);
-                            continue;
-                        }
-                    },
-                };
+        for hit in hits.iter().filter(|hit| hit.kind == ToolKind::Deleter) {
+            let interactable = unresult_or_continue!(iteractable.get(hit.item));
+            match interactable.itype() {
+                InteractableType::LineOfCode => {
+                    let (block, collider) = unresult_or_continue!(blocks.get(hit.item));
+                    bin.throw_away(&mut commands, hit.item, block.clone(), collider.clone());
+                }
+                InteractableType::LineOfCodeGlobule => {
+                    let planks = unresult_or_continue!(bundles.get(hit.item));
+                    bin.throw_away_bundle(&mut commands, hit.item, planks, &blocks);
+                }
+                _ => continue,
+            }
+            sensor_event.send(SensorEvent {
+                stype: SType::Deleter,
+                loc: None,
+            });
+        }
+    }
 
-                match ename.id.as_str() {
-                    "sensor_pp" => {
-                        let (mut loc, mat_h) = match items.get_mut(*entity1) {
-                            Ok(n) => n,
-                            Err(_) => match items.get_mut(*entity2) {
-                                Ok(v) => v,
-                                Err(_) => continue,
-                            },
-                        };
-                        loc.loc_type = LocType::Green;
-                        let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
-                        real_asset.base_color = Color::GREEN;
-                        sensor_event.send(SensorEvent {
-                            stype: SType::Painter,
-                            loc: None,
-                        });
-                    }
-                    "sensor_nn" => {
-                        let (mut loc, mat_h) = match items.get_mut(*entity1) {
-                            Ok(n) => n,
-                            Err(_) => match items.get_mut(*entity2) {
-                                Ok(v) => v,
-                                Err(_) => continue,
-                            },
-                        };
-                        loc.loc_type = LocType::Red;
-                        let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
-                        real_asset.base_color = Color::RED;
-                        sensor_event.send(SensorEvent {
-                            stype: SType::Painter,
-                            loc: None,
-                        });
-                    }
-                    "sensor_ee" => {
-                        let (mut loc, mat_h) = match items.get_mut(*entity1) {
-                            Ok(n) => n,
-                            Err(_) => match items.get_mut(*entity2) {
-                                Ok(v) => v,
-                                Err(_) => continue,
-                            },
-                        };
-                        loc.loc_type = LocType::Neutral;
-                        let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
-                        real_asset.base_color = Color::default();
-                        sensor_event.send(SensorEvent {
-                            stype: SType::Painter,
-                            loc: None,
-                        });
-                    }
-                    "sensor_deleter" => {
-                        let (interactable, e) = match iteractable.get(*entity1) {
-                            Ok(n) => (n, entity1),
-                            Err(_) => match iteractable.get(*entity2) {
-                                Ok(v) => (v, entity2),
-                                Err(_) => continue,
-                            },
-                        };
-                        match interactable.itype() {
-                            InteractableType::LineOfCode | InteractableType::LineOfCodeGlobule => {
-                                commands.entity(*e).despawn_recursive();
-                                sensor_event.send(SensorEvent {
-                                    stype: SType::Deleter,
-                                    loc: None,
-                                });
-                            }
-                            _ => {}
-                        }
-                    }
-                    "sensor_scandesk" => {
-                        let (interactable, e) = match iteractable.get(*entity1) {
-                            Ok(n) => (n, entity1),
-                            Err(_) => match iteractable.get(*entity2) {
-                                Ok(v) => (v, entity2),
-                                Err(_) => continue,
-                            },
-                        };
-
-                        if interactable.itype() == InteractableType::LineOfCode
-                            || interactable.itype() == InteractableType::LineOfCodeGlobule
-                        {
-                            let ch = match children.get(*e) {
-                                Ok(c) => c.as_ref().to_vec(),
-                                Err(_) => vec![*e],
-                            };
-                            let mut lines: Vec<LoCBlock> = Vec::with_capacity(ch.len());
-                            for line in ch {
-                                let (loc, _) = match items.get(line) {
-                                    Ok(v) => v,
-                                    Err(_) => continue,
-                                };
-                                lines.push(loc.clone());
-                            }
-                            if lines.len() == 2 {
-                                lines.reverse();
-                            }
-                            if !lines.is_empty() {
-                                sensor_event.send(SensorEvent {
-                                    stype: SType::Scanner,
-                                    loc: Some(lines),
-                                })
-                            }
-                        }
-                    }
-                    _ => continue,
+    pub fn scan(
+        mut hits: EventReader<ToolHit>,
+        mut sensor_event: EventWriter<SensorEvent>,
+        items: Query<&LoCBlock, (Without<Sensor>, Without<EName>)>,
+        iteractable: Query<&Interactable, (Without<Sensor>, Without<EName>)>,
+        bundles: Query<&OrderedChildren>,
+    ) {
+        for hit in hits.iter().filter(|hit| hit.kind == ToolKind::Scanner) {
+            let interactable = unresult_or_continue!(iteractable.get(hit.item));
+
+            if interactable.itype() == InteractableType::LineOfCode
+                || interactable.itype() == InteractableType::LineOfCodeGlobule
+            {
+                // top to bottom, same as the numbers on the planks
+                let ch = match bundles.get(hit.item) {
+                    Ok(planks) => planks.0.clone(),
+                    Err(_) => vec![hit.item],
+                };
+                let mut lines: Vec<LoCBlock> = Vec::with_capacity(ch.len());
+                for line in ch {
+                    let loc = unresult_or_continue!(items.get(line));
+                    lines.push(loc.clone());
+                }
+                if !lines.is_empty() {
+                    sensor_event.send(SensorEvent {
+                        stype: SType::Scanner,
+                        loc: Some(lines),
+                    })
                 }
             }
         }
     }
-    // pub fn manage_loc_sensor_interactions_painters(
-    //     rapier: Res<RapierContext>,
-    //     mut commands: Commands,
-    //     mut materials: ResMut<Assets<StandardMaterial>>,
-    //     sensors: Query<(&Collider, &EName, &Transform), With<Sensor>>,
-    //     mut items: Query<
-    //         (&mut LoCBlock, &Handle<StandardMaterial>),
-    //         (Without<Sensor>, Without<EName>),
-    //     >,
-    // ) {
-    //     let query_filter = QueryFilter::exclude_fixed()
-    //         .exclude_sensors()
-    //         .groups(belong_none_see_interact());
-    //     for (collider, s_name, s_transform) in sensors.iter() {
-    //         let mut intersecting_objets = vec![];
-    //         rapier.intersections_with_shape(
-    //             s_transform.translation,
-    //             s_transform.rotation,
-    //             collider,
-    //             query_filter,
-    //             |e| {
-    //                 intersecting_objets.push(e);
-    //                 true
-    //             },
-    //         );
-    //         if !intersecting_objets.is_empty() {
-    //             println!("s_name: {}", s_name.id.as_str());
-    //         }
-    //         match s_name.id.as_str() {
-    //             "sensor_deleter" => {
-    //                 for iobj in intersecting_objets {
-    //                     commands.entity(iobj).despawn_recursive();
-    //                 }
-    //             }
-    //             "sensor_ee" => {
-    //                 for iobj in intersecting_objets {
-    //                     let (mut loc, mat_h) = unresult_or_continue!(items.get_mut(iobj));
-    //                     loc.loc_type = LocType::Neutral;
-    //                     let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
-    //                     real_asset.base_color = Color::default();
-    //                 }
-    //             }
-    //             "sensor_pp" => {
-    //                 for iobj in intersecting_objets {
-    //                     let (mut loc, mat_h) = unresult_or_continue!(items.get_mut(iobj));
-    //                     loc.loc_type = LocType::Green;
-    //                     let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
-    //                     real_asset.base_color = Color::GREEN;
-    //                 }
-    //             }
-    //             "sensor_nn" => {
-    //                 for iobj in intersecting_objets {
-    //                     let (mut loc, mat_h) = unresult_or_continue!(items.get_mut(iobj));
-    //                     loc.loc_type = LocType::Red;
-    //                     let mut real_asset = unwrap_or_continue!(materials.get_mut(mat_h));
-    //                     real_asset.base_color = Color::RED;
-    //                 }
-    //             }
-    //             _ => continue,
-    //         }
-    //     }
-    // }
+
+    /// Takes planks off a bundle and lays them out in order on the splitter's tray.
+    /// A bundle left with a single plank is split completely
+    pub fn split(
+        mut commands: Commands,
+        mut hits: EventReader<ToolHit>,
+        mut sensor_event: EventWriter<SensorEvent>,
+        outputs: StationOutputs,
+        colliders: Query<&Collider>,
+        mut bundles: Query<&mut OrderedChildren>,
+    ) {
+        for hit in hits.iter() {
+            let take = match hit.kind {
+                ToolKind::Splitter(take) => take,
+                _ => continue,
+            };
+            let mut planks = unresult_or_continue!(bundles.get_mut(hit.item));
+            let all = planks.0.clone();
+            if all.is_empty() {
+                continue;
+            }
+            let (freed, kept) = match take {
+                SplitOff::All => (all.clone(), Vec::new()),
+                SplitOff::First => (all[..1].to_vec(), all[1..].to_vec()),
+                SplitOff::Last => (all[all.len() - 1..].to_vec(), all[..all.len() - 1].to_vec()),
+            };
+            let (freed, kept) = match kept.len() {
+                0 | 1 => (all, Vec::new()),
+                _ => (freed, kept),
+            };
+
+            let tray = outputs.find(hit.tool, "tray");
+
+            commands.entity(hit.item).remove_children(&freed);
+            for (i, plank) in freed.iter().enumerate() {
+                let mut trans = tray;
+                trans.translation += tray.rotation * Vec3::X * TRAY_SPACING * i as f32;
+                commands
+                    .entity(*plank)
+                    .insert_bundle(TransformBundle::from_transform(trans))
+                    .insert(plank_collider())
+                    .insert(Interactable::LOC)
+                    .insert(RigidBody::Dynamic)
+                    .insert(ActiveCollisionTypes::all())
+                    .insert(interactable_dynamic_body());
+            }
+
+            if kept.is_empty() {
+                commands.entity(hit.item).despawn();
+            } else {
+                planks.0 = kept;
+                planks.relayout(&mut commands, hit.item, &colliders);
+            }
+            sensor_event.send(SensorEvent {
+                stype: SType::Splitter,
+                loc: None,
+            });
+        }
+    }
+
+    /// Copies a single plank, paint included, onto the copier's `out` location
+    /// as many times as the level allows
+    pub fn duplicate(
+        mut hits: EventReader<ToolHit>,
+        mut sensor_event: EventWriter<SensorEvent>,
+        mut term_write: EventWriter<TermWrite>,
+        mut made: ResMut<CopiesMade>,
+        mut spawner: PlankSpawner,
+        levels: Res<Levels>,
+        outputs: StationOutputs,
+        planks: Query<(&LoCBlock, &PlankLabel), (Without<PlankCopy>, With<Interactable>)>,
+        labels: Query<&LineOfCode>,
+    ) {
+        for hit in hits.iter().filter(|hit| hit.kind == ToolKind::Duplicator) {
+            let (block, label) = unresult_or_continue!(planks.get(hit.item));
+            let loc = unresult_or_continue!(labels.get(label.0));
+            let level = &levels.levels[levels.current];
+            if matches!(level.max_copies, Some(max) if made.0 >= max) {
+                term_write.send(TermWrite {
+                    s: format!(
+                        "\n{}copier: no copies left for this job{}\n>>",
+                        ansi::YELLOW,
+                        ansi::RESET
+                    ),
+                });
+                continue;
+            }
+            // after every plank the level spawned with
+            let slot = level.code.len() + made.0;
+            let copy = spawner.spawn(loc, block.loc_type, slot, outputs.find(hit.tool, "out"));
+            spawner.commands.entity(copy).insert(PlankCopy);
+            made.0 += 1;
+            sensor_event.send(SensorEvent {
+                stype: SType::Duplicator,
+                loc: None,
+            });
+        }
+    }
+
+    /// Sorts a bundle into the order its lines have in the level, for a few seconds off the clock.
+    /// Lines that aren't in the level go to the bottom, nothing gets painted or deleted
+    pub fn lint(
+        mut commands: Commands,
+        mut hits: EventReader<ToolHit>,
+        mut sensor_event: EventWriter<SensorEvent>,
+        mut term_write: EventWriter<TermWrite>,
+        mut timer: ResMut<LevelTimer>,
+        score_config: Res<ScoreConfig>,
+        levels: Res<Levels>,
+        colliders: Query<&Collider>,
+        blocks: Query<&LoCBlock>,
+        mut bundles: Query<&mut OrderedChildren>,
+    ) {
+        for hit in hits.iter().filter(|hit| hit.kind == ToolKind::Linter) {
+            let mut planks = unresult_or_continue!(bundles.get_mut(hit.item));
+            let sorted = level_order(&planks.0, &levels.levels[levels.current].code, &blocks);
+            if sorted == planks.0 {
+                term_write.send(TermWrite {
+                    s: format!(
+                        "\n{}linter: bundle is already in order{}\n>>",
+                        ansi::GREEN,
+                        ansi::RESET
+                    ),
+                });
+                continue;
+            }
+            planks.0 = sorted;
+            planks.relayout(&mut commands, hit.item, &colliders);
+            timer.spend(Duration::from_secs(score_config.linter_cost_secs));
+            term_write.send(TermWrite {
+                s: format!(
+                    "\n{}linter: bundle sorted, {}s taken off the clock{}\n>>",
+                    ansi::YELLOW,
+                    score_config.linter_cost_secs,
+                    ansi::RESET
+                ),
+            });
+            sensor_event.send(SensorEvent {
+                stype: SType::Linter,
+                loc: None,
+            });
+        }
+    }
+
+    fn reset_copies(mut made: ResMut<CopiesMade>) {
+        made.0 = 0;
+    }
 }
 
 impl Plugin for ToolsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SensorEvent>();
-        app.add_system(ToolsPlugin::loc_sensor_interacts.run_in_state(GameState::InOffice));
+        app.add_event::<SensorEvent>()
+            .add_event::<ToolHit>()
+            .init_resource::<CopiesMade>();
+        app.add_system(
+            ToolsPlugin::detect_tool_hits
+                .run_in_state(GameState::InOffice)
+                .label(ToolSystemLabel::Detect),
+        );
+        app.add_system_set(
+            ConditionSet::new()
+                .run_in_state(GameState::InOffice)
+                .after(ToolSystemLabel::Detect)
+                .with_system(ToolsPlugin::paint)
+                .with_system(ToolsPlugin::delete)
+                .with_system(ToolsPlugin::scan)
+                .with_system(ToolsPlugin::split)
+                .with_system(ToolsPlugin::duplicate)
+                .with_system(ToolsPlugin::lint)
+                .into(),
+        );
+        app.add_system(
+            ToolsPlugin::reset_copies
+                .run_in_state(GameState::InOffice)
+                .run_if(NewLevel::has_triggered),
+        );
         app.add_system(
             ToolsPlugin::spawn_hammer
                 .run_in_state(GameState::InOffice)
@@ -278,3 +515,98 @@ impl Plugin for ToolsPlugin {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::code::{CodeColor, Diff};
+    use bevy::ecs::system::SystemState;
+
+    /// the lines of `planks` in the order `level_order` puts them in
+    fn sorted(planks: &[&str], code: &[&str]) -> Vec<String> {
+        let mut world = World::new();
+        let planks = planks
+            .iter()
+            .map(|line| {
+                world
+                    .spawn()
+                    .insert(LoCBlock {
+                        line_of_code: line.to_string(),
+                        loc_type: LocType::Neutral,
+                    })
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        let code = code
+            .iter()
+            .map(|line| LineOfCode {
+                diff: Diff::Eq,
+                color: CodeColor::None,
+                code: line.to_string(),
+                typo: None,
+            })
+            .collect::<Vec<_>>();
+        let mut state = SystemState::<Query<&LoCBlock>>::new(&mut world);
+        let blocks = state.get(&world);
+        level_order(&planks, &code, &blocks)
+            .into_iter()
+            .map(|e| blocks.get(e).unwrap().line_of_code.clone())
+            .collect()
+    }
+
+    #[test]
+    fn level_order_follows_the_code() {
+        assert_eq!(sorted(&["c", "a", "b"], &["a", "b", "c"]), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn level_order_puts_extra_lines_last() {
+        assert_eq!(
+            sorted(&["x", "b", "a", "y"], &["a", "b"]),
+            ["a", "b", "x", "y"]
+        );
+    }
+
+    #[test]
+    fn level_order_duplicates_claim_the_next_match() {
+        assert_eq!(
+            sorted(&["}", "}", "}", "f {", "g {"], &["f {", "}", "g {", "}"]),
+            ["f {", "}", "g {", "}", "}"]
+        );
+    }
+
+    #[test]
+    fn tool_names() {
+        use ToolKind::*;
+        for (name, kind) in [
+            ("sensor_pp", Painter(LocType::Green)),
+            ("sensor_nn_2", Painter(LocType::Red)),
+            ("sensor_ee", Painter(LocType::Neutral)),
+            ("sensor_deleter", Deleter),
+            ("sensor_scandesk", Scanner),
+            ("sensor_splitter", Splitter(SplitOff::All)),
+            ("sensor_popfirst", Splitter(SplitOff::First)),
+            ("sensor_poplast_b", Splitter(SplitOff::Last)),
+            ("sensor_copier", Duplicator),
+            ("sensor_duplicator", Duplicator),
+            ("sensor_typewriter", Typewriter),
+            ("sensor_linter", Linter),
+        ] {
+            assert_eq!(ToolKind::from_name(name), Some(kind), "{name}");
+        }
+    }
+
+    #[test]
+    fn unknown_tool_names() {
+        for name in [
+            "",
+            "sensor_",
+            "sensor_blender",
+            "pp",
+            "sensorpp",
+            "point3d_pp",
+        ] {
+            assert_eq!(ToolKind::from_name(name), None, "{name}");
+        }
+    }
+}
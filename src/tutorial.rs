@@ -0,0 +1,170 @@
+use crate::{
+    bindings::{Action, InputBindings},
+    code::LoCBlock,
+    interactable::{Interactable, InteractableType},
+    player::{interactions::OrderedChildren, MouseInteraction},
+    prelude::*,
+    terminal::{ansi, TermWrite, TerminalCommand},
+    tools::{SType, SensorEvent},
+    ui::TutorialText,
+    viewmodel::{ViewModel, ViewModelHold},
+};
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tutorial>()
+            .add_enter_system(GameState::InOffice, Tutorial::reset)
+            .add_system(Tutorial::advance.run_in_state(GameState::InOffice))
+            .add_system(Tutorial::update_ui.run_in_state(GameState::InOffice));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    PickUp,
+    Paint,
+    Attach,
+    Smash,
+    Delete,
+    Scan,
+    Finish,
+}
+
+const STEPS: &[TutorialStep] = &[
+    TutorialStep::PickUp,
+    TutorialStep::Paint,
+    TutorialStep::Attach,
+    TutorialStep::Smash,
+    TutorialStep::Delete,
+    TutorialStep::Scan,
+    TutorialStep::Finish,
+];
+
+impl TutorialStep {
+    /// with `{Action}` where the binding goes, see `InputBindings::fill`
+    pub fn instructions(&self) -> &'static str {
+        use TutorialStep::*;
+        match self {
+            PickUp => {
+                "[e]xit the terminal, look at a plank on the desk and {Interact} to pick it up"
+            }
+            Paint => "throw a plank through a painter with {Throw} to colour it",
+            Attach => "hold a plank and {Interact} another plank to attach them into a bundle",
+            Smash => "pick up the hammer and {Swing} a bundle to smash it apart",
+            Delete => "throw a plank that doesn't belong into the deleter",
+            Scan => "drop your finished plank or bundle onto the scan desk",
+            Finish => "go back to a terminal and [f]inish to submit the job",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Tutorial {
+    /// index into `STEPS`, `None` when the tutorial isn't running
+    pub step: Option<usize>,
+}
+
+impl Tutorial {
+    pub fn current(&self) -> Option<TutorialStep> {
+        self.step.and_then(|i| STEPS.get(i)).copied()
+    }
+
+    fn announce(&self, bindings: &InputBindings) -> String {
+        match (self.step, self.current()) {
+            (Some(i), Some(step)) => format!(
+                "\n{}tutorial {}/{}:{} {}\n>>",
+                ansi::YELLOW,
+                i + 1,
+                STEPS.len(),
+                ansi::RESET,
+                bindings.fill(step.instructions())
+            ),
+            _ => format!("\n{}tutorial complete!{}\n>>", ansi::GREEN, ansi::RESET),
+        }
+    }
+
+    fn reset(mut tutorial: ResMut<Tutorial>) {
+        tutorial.step = None;
+    }
+
+    fn advance(
+        mut tutorial: ResMut<Tutorial>,
+        mut term_cmds: EventReader<TerminalCommand>,
+        mut sensors: EventReader<SensorEvent>,
+        mut mouse: EventReader<MouseInteraction>,
+        mut term_write: EventWriter<TermWrite>,
+        viewmodel: Query<&ViewModel>,
+        interactable: Query<&Interactable>,
+        bundles: Query<(), With<OrderedChildren>>,
+        reparented: Query<&Parent, (With<LoCBlock>, Changed<Parent>)>,
+        bindings: Res<InputBindings>,
+    ) {
+        let mut submitted = false;
+        for cmd in term_cmds.iter() {
+            match cmd {
+                TerminalCommand::Tutorial => {
+                    tutorial.step = Some(0);
+                    term_write.send(TermWrite {
+                        s: tutorial.announce(&bindings),
+                    });
+                }
+                TerminalCommand::TutorialStop => tutorial.step = None,
+                TerminalCommand::Send => submitted = true,
+                _ => {}
+            }
+        }
+        let sensed = sensors.iter().map(|ev| &ev.stype).collect::<Vec<_>>();
+        let clicked = mouse
+            .iter()
+            .filter(|ev| ev.action == Action::Swing)
+            .filter_map(|ev| interactable.get(ev.with).ok())
+            .map(|i| i.itype())
+            .collect::<Vec<_>>();
+
+        let step = match tutorial.current() {
+            Some(step) => step,
+            None => return,
+        };
+        let holding = viewmodel
+            .get_single()
+            .map(|vm| vm.holding())
+            .unwrap_or(ViewModelHold::Empty);
+        let done = match step {
+            TutorialStep::PickUp => {
+                matches!(holding, ViewModelHold::LoC | ViewModelHold::LoCBundle)
+            }
+            TutorialStep::Paint => sensed.iter().any(|s| matches!(s, SType::Painter)),
+            // a plank just went into a bundle, a new one or one that was already there
+            TutorialStep::Attach => reparented.iter().any(|p| bundles.contains(p.get())),
+            TutorialStep::Smash => {
+                holding == ViewModelHold::Hammer
+                    && clicked.contains(&InteractableType::LineOfCodeGlobule)
+            }
+            TutorialStep::Delete => sensed.iter().any(|s| matches!(s, SType::Deleter)),
+            TutorialStep::Scan => sensed.iter().any(|s| matches!(s, SType::Scanner)),
+            TutorialStep::Finish => submitted,
+        };
+        if done {
+            tutorial.step = tutorial.step.map(|i| i + 1);
+            term_write.send(TermWrite {
+                s: tutorial.announce(&bindings),
+            });
+            if tutorial.current().is_none() {
+                tutorial.step = None;
+            }
+        }
+    }
+
+    fn update_ui(
+        tutorial: Res<Tutorial>,
+        bindings: Res<InputBindings>,
+        mut text: Query<&mut Text, With<TutorialText>>,
+    ) {
+        text.single_mut().sections[0].value = match tutorial.current() {
+            Some(step) => bindings.fill(step.instructions()).to_uppercase(),
+            None => String::new(),
+        };
+    }
+}
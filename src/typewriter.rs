@@ -0,0 +1,193 @@
+use crate::{
+    audio::events::{InteractSoundEvent, InteractSoundType},
+    code::{LineOfCode, LoCBlock, PlankLabel},
+    interactable::Interactable,
+    player::fsm::{PlayerState, PlayerStateMachine},
+    prelude::*,
+    terminal::{
+        ansi, Align, FontAtlas, TerminalScreenTarget, TextLayout, TextSprite, ATLAS_CHAR_H,
+        ATLAS_CHAR_W, PROPMPT, TERM_COLS, TERM_H, TERM_W,
+    },
+    tools::{ToolHit, ToolKind},
+    unresult_or_continue,
+};
+
+/// below the terminals and the preview on the 2D canvas
+const TYPEWRITER_OFFSET: Vec3 = Vec3::from_array([0., 4000., 0.]);
+const HEADER: &str = "typewriter: [enter] to save, an empty line leaves it as it was";
+
+pub struct TypewriterPlugin;
+
+impl Plugin for TypewriterPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Typewriter>()
+            .add_enter_system(GameState::InOffice, Typewriter::spawn)
+            .add_system(Typewriter::open.run_in_state(GameState::InOffice))
+            .add_system(
+                Typewriter::take_input
+                    .run_in_state(GameState::InOffice)
+                    .run_if(Typewriter::is_open),
+            )
+            .add_system(Typewriter::show_or_hide.run_in_state(GameState::InOffice));
+    }
+}
+
+/// The text being edited, drawn onto the overlay
+#[derive(Component)]
+pub struct TypewriterText;
+
+/// UI node showing the typewriter's screen
+#[derive(Component)]
+pub struct TypewriterOverlay;
+
+#[derive(Default)]
+pub struct Typewriter {
+    /// the plank being edited
+    pub editing: Option<Entity>,
+    /// where the plank's text starts in the `TypewriterText`
+    pub start: usize,
+}
+
+impl Typewriter {
+    pub fn is_open(typewriter: Res<Typewriter>) -> bool {
+        typewriter.editing.is_some()
+    }
+
+    fn spawn(mut commands: Commands, font: Res<FontAtlas>, mut images: ResMut<Assets<Image>>) {
+        let target = TerminalScreenTarget::new(&mut images);
+        // like the carriage of a real one, the paper moves and where you type stays put
+        TextSprite::new(String::new(), font.atlas.clone(), 1.0)
+            .with_layout(TextLayout {
+                max_cols: Some(TERM_COLS),
+                align: Align::Right,
+                ..default()
+            })
+            .spawn(&mut commands, |mut parent| {
+                parent.insert(TypewriterText);
+                parent.insert(Transform::from_translation(
+                    Vec3::new(
+                        (ATLAS_CHAR_W - TERM_W) / 2.0,
+                        (TERM_H - ATLAS_CHAR_H) / 2.,
+                        0.,
+                    ) + TYPEWRITER_OFFSET,
+                ));
+            });
+        target.set_up_2d(&mut commands, TYPEWRITER_OFFSET);
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    position_type: PositionType::Absolute,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                color: Color::NONE.into(),
+                visibility: Visibility { is_visible: false },
+                ..Default::default()
+            })
+            .insert(TypewriterOverlay)
+            .with_children(|b| {
+                b.spawn_bundle(ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(60.0), Val::Percent(60.0)),
+                        ..Default::default()
+                    },
+                    image: target.image.clone().into(),
+                    ..Default::default()
+                });
+            });
+    }
+
+    /// Starts editing a plank dropped into the typewriter
+    fn open(
+        mut typewriter: ResMut<Typewriter>,
+        mut hits: EventReader<ToolHit>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        planks: Query<&PlankLabel, (With<LoCBlock>, With<Interactable>)>,
+        labels: Query<&LineOfCode>,
+        mut text: Query<&mut TextSprite, With<TypewriterText>>,
+    ) {
+        for hit in hits.iter().filter(|hit| hit.kind == ToolKind::Typewriter) {
+            if typewriter.editing.is_some() {
+                return;
+            }
+            let label = unresult_or_continue!(planks.get(hit.item));
+            let loc = unresult_or_continue!(labels.get(label.0));
+            player_state.change_state(PlayerState::Interacting);
+            // e.g. still holding something
+            if player_state.state() != PlayerState::Interacting {
+                continue;
+            }
+
+            let mut text_sprite = text.single_mut();
+            *text_sprite = TextSprite::new(
+                format!("{}{HEADER}{}\n{PROPMPT}", ansi::YELLOW, ansi::RESET),
+                text_sprite.atlas.clone(),
+                text_sprite.scale,
+            )
+            .with_layout(text_sprite.layout);
+            typewriter.start = text_sprite.len();
+            text_sprite.add_str(&loc.code);
+            typewriter.editing = Some(hit.item);
+        }
+    }
+
+    fn take_input(
+        mut typewriter: ResMut<Typewriter>,
+        mut interact_sfx_event: EventWriter<InteractSoundEvent>,
+        mut keystrokes: EventReader<ReceivedCharacter>,
+        keys: Res<Input<KeyCode>>,
+        mut player_state: ResMut<PlayerStateMachine>,
+        mut text: Query<&mut TextSprite, With<TypewriterText>>,
+        mut planks: Query<(&mut LoCBlock, &PlankLabel)>,
+        mut labels: Query<&mut LineOfCode>,
+    ) {
+        let mut text_sprite = text.single_mut();
+        let entered = text_sprite.take_typing(&mut keystrokes, &keys, typewriter.start);
+        for _ in keys.get_just_pressed() {
+            interact_sfx_event.send(InteractSoundEvent {
+                int_type: InteractSoundType::TerminalType,
+            });
+        }
+
+        if !entered {
+            return;
+        }
+        let plank = typewriter.editing.take().unwrap();
+        player_state.change_state(PlayerState::Idle);
+
+        let edited = text_sprite
+            .text
+            .lines()
+            .last()
+            .and_then(|ln| ln.strip_prefix(PROPMPT))
+            .unwrap_or_default()
+            .to_owned();
+        if edited.trim().is_empty() {
+            return;
+        }
+        let (mut block, label) = match planks.get_mut(plank) {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+        // the label re-renders itself from its `LineOfCode`
+        if let Ok(mut loc) = labels.get_mut(label.0) {
+            loc.code = edited.clone();
+        }
+        block.line_of_code = edited;
+    }
+
+    fn show_or_hide(
+        typewriter: Res<Typewriter>,
+        mut overlay: Query<&mut Visibility, With<TypewriterOverlay>>,
+    ) {
+        if !typewriter.is_changed() {
+            return;
+        }
+        for mut vis in overlay.iter_mut() {
+            vis.is_visible = typewriter.editing.is_some();
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::bindings::InputBindings;
 use crate::interactable::{Interactable, InteractableType};
 use crate::player::fsm::{PlayerState, PlayerStateMachine};
 use crate::player::PlayerLookingAt;
@@ -53,6 +54,21 @@ pub struct ITextShadow;
 #[derive(Component)]
 pub struct TimerText;
 
+#[derive(Component)]
+pub struct MailText;
+
+#[derive(Component)]
+pub struct TutorialText;
+
+#[derive(Component)]
+pub struct HotbarText;
+
+#[derive(Component)]
+pub struct ThrowChargeBar;
+
+#[derive(Component)]
+pub struct ThrowChargeFill;
+
 #[derive(Component)]
 pub struct Crosshair;
 
@@ -238,6 +254,27 @@ pub fn spawn_gui_inoffice(
                                             }),
                                     ).insert(InteractText);
                             });
+                            b.spawn()
+                                .insert_bundle(NodeBundle {
+                                    style: Style {
+                                        size: Size::new(Val::Px(120.0), Val::Px(8.0)),
+                                        margin: UiRect::all(Val::Px(5.0)),
+                                        ..Default::default()
+                                    },
+                                    color: Color::NONE.into(),
+                                    ..Default::default()
+                                })
+                                .insert(ThrowChargeBar)
+                                .with_children(|b| {
+                                    b.spawn().insert_bundle(NodeBundle {
+                                        style: Style {
+                                            size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                                            ..Default::default()
+                                        },
+                                        color: Color::WHITE.into(),
+                                        ..Default::default()
+                                    }).insert(ThrowChargeFill);
+                            });
                         });
                 });
             // timer top right
@@ -268,6 +305,43 @@ pub fn spawn_gui_inoffice(
                         ..Default::default()
                     }))
                     .insert(TimerText);
+                    b.spawn().insert_bundle(TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: ui_assets.font.clone(),
+                            font_size: 20.0,
+                            color: Color::YELLOW,
+                        },
+                    ).with_style(Style {
+                        margin: UiRect::all(Val::Px(5.0)),
+                        ..Default::default()
+                    }))
+                    .insert(MailText);
+                    b.spawn().insert_bundle(TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: ui_assets.font.clone(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                    ).with_style(Style {
+                        margin: UiRect::all(Val::Px(5.0)),
+                        max_size: Size::new(Val::Px(400.0), Val::Auto),
+                        ..Default::default()
+                    }))
+                    .insert(TutorialText);
+                    b.spawn().insert_bundle(TextBundle::from_section(
+                        "",
+                        TextStyle {
+                            font: ui_assets.font.clone(),
+                            font_size: 20.0,
+                            color: Color::WHITE,
+                        },
+                    ).with_style(Style {
+                        margin: UiRect::all(Val::Px(5.0)),
+                        ..Default::default()
+                    }))
+                    .insert(HotbarText);
                 });
 
         });
@@ -439,6 +513,7 @@ pub fn update_interact_text(
     interactable: Query<&Interactable>,
     mut text: Query<&mut Text, With<InteractText>>,
     mut t_color: Query<&mut UiColor, (With<ITextShadow>, Without<InteractText>)>,
+    bindings: Res<InputBindings>,
 ) {
     let mut itext = text.single_mut();
     let mut text_color = t_color.single_mut();
@@ -458,7 +533,7 @@ pub fn update_interact_text(
             match (holding, interactable) {
                 (_, UiInteractable::Terminal) => {
                     if looking_at.dist < 1.5 {
-                        itext.sections[0].value = "[MOUSE1] Interact".to_string();
+                        itext.sections[0].value = bindings.fill("{OpenTerminal} Interact");
                         *text_color = SHADOW.into();
                     }
                 }
@@ -467,25 +542,25 @@ pub fn update_interact_text(
                     *text_color = TRANSPARENT.into();
                 }
                 (ViewModelHold::Empty, _) => {
-                    itext.sections[0].value = "[MOUSE1] Pickup".to_string();
+                    itext.sections[0].value = bindings.fill("{Interact} Pickup");
                     *text_color = SHADOW.into();
                 }
                 (ViewModelHold::Hammer, UiInteractable::None) => {
-                    itext.sections[0].value = "[MOUSE1] Swing".to_string();
+                    itext.sections[0].value = bindings.fill("{Swing} Swing");
                     *text_color = SHADOW.into();
                 }
                 (
                     ViewModelHold::Hammer,
                     UiInteractable::LineOfCodeGlobule | UiInteractable::LineOfCode,
                 ) => {
-                    itext.sections[0].value = "[MOUSE1] Swing\n[MOUSE2] Swap".to_string();
+                    itext.sections[0].value = bindings.fill("{Swing} Swing\n{Swap} Swap");
                     *text_color = SHADOW.into();
                 }
                 (
                     ViewModelHold::LoC | ViewModelHold::LoCBundle,
                     UiInteractable::LineOfCode | UiInteractable::LineOfCodeGlobule,
                 ) => {
-                    itext.sections[0].value = "[MOUSE1] Attach\n[MOUSE2] Swap".to_string();
+                    itext.sections[0].value = bindings.fill("{Interact} Attach\n{Swap} Swap");
                     *text_color = SHADOW.into();
                 }
                 (_, _) => {}
@@ -493,11 +568,12 @@ pub fn update_interact_text(
         }
         None => match holding {
             ViewModelHold::Hammer => {
-                itext.sections[0].value = "[MOUSE1] Swing\n[MOUSE2] Throw".to_string();
+                itext.sections[0].value =
+                    bindings.fill("{Swing} Swing\n{Throw} Hold to Throw, Tap to Drop");
                 *text_color = SHADOW.into();
             }
             ViewModelHold::LoCBundle | ViewModelHold::LoC => {
-                itext.sections[0].value = "[MOUSE2] Throw".to_string();
+                itext.sections[0].value = bindings.fill("{Throw} Hold to Throw, Tap to Drop");
                 *text_color = SHADOW.into();
             }
             ViewModelHold::Empty => {
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Component)]
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Component)]
 pub enum ViewModelHold {
     Empty,
     Hammer,